@@ -0,0 +1,37 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use rexif::ParseOptions;
+
+// No input may make any public entry point panic. parse_file and read_file
+// only read the file into a buffer for parse_buffer, so they are covered here.
+fuzz_target!(|data: &[u8]| {
+    let _ = rexif::parse_buffer(data);
+    let _ = rexif::parse_buffer_quiet(data);
+    let _ = rexif::parse_buffer_with_options(data, &ParseOptions::strict());
+    let (res, warnings) = rexif::parse_buffer_with_options(data, &ParseOptions::lenient());
+    for w in &warnings {
+        let _ = (w.to_string(), w.offset(), w.ifd_kind(), w.entry_index());
+    }
+
+    match res {
+        Ok(exif) => {
+            for entry in &exif.entries {
+                let _ = (entry.value.to_string(), entry.value.to_f64(0), entry.value.as_str());
+            }
+            let _ = exif.gps();
+            let _ = (exif.date_time(), exif.date_time_original(), exif.date_time_digitized());
+            let _ = (exif.orientation(), exif.flash(), exif.lens(), exif.exposure());
+            let _ = exif.serialize();
+            let _ = rexif::write_jpeg(data, &exif);
+            let _ = rexif::write_tiff(data, &exif);
+            let _ = (rexif::write_png(data, &exif), rexif::write_webp(data, &exif));
+            let _ = rexif::write_patched(data, &exif);
+            let mut exif = exif;
+            let _ = exif.set_byte_order(if exif.le { rexif::ByteOrder::BigEndian } else { rexif::ByteOrder::LittleEndian });
+            let _ = exif.serialize();
+        },
+        Err(e) => {
+            let _ = (e.to_string(), e.offset());
+        },
+    }
+});
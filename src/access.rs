@@ -0,0 +1,63 @@
+use super::rational::*;
+use super::types::*;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Position in `ExifData::entries` of the first entry of each tag, per IFD and overall
+#[derive(Clone, Default)]
+pub(crate) struct TagIndex {
+    by_kind: HashMap<(IfdKind, ExifTag), usize>,
+    by_tag: HashMap<ExifTag, usize>,
+}
+
+impl TagIndex {
+    pub(crate) fn new(entries: &[ExifEntry]) -> Self {
+        let mut index = TagIndex::default();
+        for (i, e) in entries.iter().enumerate() {
+            index.by_kind.entry((e.kind, e.tag)).or_insert(i);
+            index.by_tag.entry(e.tag).or_insert(i);
+        }
+        index
+    }
+}
+
+impl fmt::Debug for TagIndex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TagIndex({} tags)", self.by_tag.len())
+    }
+}
+
+impl ExifData {
+    /// First entry of a tag, in whichever IFD it is found
+    pub fn get(&self, tag: ExifTag) -> Option<&ExifEntry> {
+        // `entries` is public, so the index may be stale. Check the entry it points to,
+        // and search the slow way if it is not the expected one.
+        match self.index.by_tag.get(&tag).and_then(|&i| self.entries.get(i)) {
+            Some(e) if e.tag == tag => Some(e),
+            _ => self.entries.iter().find(|e| e.tag == tag),
+        }
+    }
+
+    /// First entry of a tag in a specific IFD
+    pub fn get_in(&self, kind: IfdKind, tag: ExifTag) -> Option<&ExifEntry> {
+        match self.index.by_kind.get(&(kind, tag)).and_then(|&i| self.entries.get(i)) {
+            Some(e) if e.tag == tag && e.kind == kind => Some(e),
+            _ => self.entries.iter().find(|e| e.tag == tag && e.kind == kind),
+        }
+    }
+
+    /// Value of an ASCII (or UTF-8) tag
+    pub fn get_str(&self, tag: ExifTag) -> Option<&str> {
+        self.get(tag)?.value.as_str()
+    }
+
+    /// First value of an integer tag, whether it is stored as BYTE, SHORT or LONG
+    pub fn get_u32(&self, tag: ExifTag) -> Option<u32> {
+        self.get(tag)?.value.to_u32(0)
+    }
+
+    /// First value of a rational tag. Integer values are returned with a denominator of 1.
+    pub fn get_rational(&self, tag: ExifTag) -> Option<URational> {
+        self.get(tag)?.value.to_urational(0)
+    }
+}
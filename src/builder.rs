@@ -0,0 +1,168 @@
+use std::borrow::Cow;
+use super::exif::*;
+use super::ifdformat::*;
+use super::rational::*;
+use super::types::*;
+
+impl ExifEntry {
+    /// Entry of `tag` in the IFD `kind`, with its raw data encoded from `value` in
+    /// little-endian order if `le`, big-endian otherwise.
+    ///
+    /// The value must have the format and count that the tag takes, or one of the
+    /// formats the TIFF and DNG specifications also allow. Text that is not ASCII
+    /// takes the Exif 3.0 UTF-8 type. The readable string does
+    /// not take other tags into account, e.g. the ResolutionUnit of XResolution.
+    pub fn new(kind: IfdKind, tag: ExifTag, value: TagValue, le: bool) -> Result<ExifEntry, ExifError> {
+        let code = ((tag as u32) & 0xffff) as u16;
+        let (known, unit, format, min_count, max_count, more_readable) = tag_to_exif(kind, code);
+        if tag == ExifTag::UnknownToMe || known != tag {
+            return Err(ExifError::TagNotInIfd { tag, kind });
+        }
+
+        let (found, count, data) = tag_value_encode(&value, le);
+        // Exif 3.0 allows UTF-8 wherever ASCII is expected
+        let utf8_for_ascii = format == IfdFormat::Ascii && found == IfdFormat::Utf8;
+        if found != format && !utf8_for_ascii && !alternative_formats(tag).contains(&found) {
+            return Err(ExifError::WrongTagFormat { tag, expected: format, found });
+        }
+        if min_count != -1 && (i64::from(count) < i64::from(min_count) || i64::from(count) > i64::from(max_count)) {
+            return Err(ExifError::WrongTagCount { tag, min: min_count as u32, max: max_count as u32, found: count });
+        }
+
+        let ifd = IfdEntry::new(code, found, count, data, le);
+
+        Ok(ExifEntry {
+            namespace: Namespace::Standard,
+            ifd,
+            tag,
+            unit: unit.into(),
+            value_more_readable: more_readable(&value).unwrap_or(Cow::Borrowed("")),
+            value,
+            kind,
+        })
+    }
+}
+
+/// Creates `ExifData` from scratch.
+///
+/// Each tag is put in the IFD the standard prescribes, and the ExifOffset, GPSOffset
+/// and InteropOffset pointers are added as needed. Values are checked against the
+/// tag tables when `build()` is called.
+///
+/// ```
+/// use rexif::{ByteOrder, ExifBuilder, ExifTag};
+///
+/// let exif = ExifBuilder::new(ByteOrder::BigEndian)
+///     .set(ExifTag::Artist, "Jane Doe")
+///     .set(ExifTag::ISOSpeedRatings, 200u16)
+///     .set_gps(48.8584, 2.2945)
+///     .build()
+///     .unwrap();
+/// assert_eq!(exif.get_str(ExifTag::Artist), Some("Jane Doe"));
+/// assert!(exif.get(ExifTag::ExifOffset).is_some());
+/// ```
+#[derive(Debug)]
+pub struct ExifBuilder {
+    byte_order: ByteOrder,
+    mime: &'static str,
+    values: Vec<(ExifTag, TagValue)>,
+    error: Option<ExifError>,
+}
+
+impl ExifBuilder {
+    /// Empty builder. The MIME type is "image/jpeg", so that `serialize()` gives
+    /// the payload of a JPEG APP1 segment.
+    pub fn new(byte_order: ByteOrder) -> Self {
+        ExifBuilder {
+            byte_order,
+            mime: "image/jpeg",
+            values: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// MIME type of the `ExifData`, "image/jpeg" or "image/tiff"
+    pub fn mime(mut self, mime: &'static str) -> Self {
+        self.mime = mime;
+        self
+    }
+
+    /// Sets a tag, replacing the value set before, if any.
+    ///
+    /// The pointer tags ExifOffset, GPSOffset and InteropOffset are managed by the
+    /// builder; values set for them are ignored.
+    pub fn set<V: Into<TagValue>>(mut self, tag: ExifTag, value: V) -> Self {
+        self.values.retain(|&(t, _)| t != tag);
+        self.values.push((tag, value.into()));
+        self
+    }
+
+    /// Sets GPSLatitude, GPSLongitude and their references from decimal degrees,
+    /// positive to the north and east, to the nearest millisecond of arc.
+    pub fn set_gps(mut self, latitude: f64, longitude: f64) -> Self {
+        let invalid = if !(-90.0..=90.0).contains(&latitude) {
+            Some((ExifTag::GPSLatitude, latitude))
+        } else if !(-180.0..=180.0).contains(&longitude) {
+            Some((ExifTag::GPSLongitude, longitude))
+        } else {
+            None
+        };
+        if let Some((tag, degrees)) = invalid {
+            if self.error.is_none() {
+                self.error = Some(ExifError::InvalidTagValue { tag, reason: format!("{} degrees is out of range", degrees) });
+            }
+            return self;
+        }
+
+        self.set(ExifTag::GPSLatitudeRef, if latitude < 0.0 { "S" } else { "N" })
+            .set(ExifTag::GPSLatitude, dms(latitude))
+            .set(ExifTag::GPSLongitudeRef, if longitude < 0.0 { "W" } else { "E" })
+            .set(ExifTag::GPSLongitude, dms(longitude))
+    }
+
+    /// Encodes the values. Fails on the first value that does not fit its tag.
+    pub fn build(self) -> Result<ExifData, ExifError> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        let le = self.byte_order.is_le();
+
+        let mut entries = Vec::with_capacity(self.values.len() + 4);
+        for (tag, value) in self.values {
+            if is_pointer(tag) {
+                continue;
+            }
+            entries.push(ExifEntry::new(tag_ifd(tag), tag, value, le)?);
+        }
+
+        // The GPS IFD must start with its version
+        let gps = entries.iter().any(|e| e.kind == IfdKind::Gps);
+        if gps && !entries.iter().any(|e| e.tag == ExifTag::GPSVersionID) {
+            entries.push(ExifEntry::new(IfdKind::Gps, ExifTag::GPSVersionID, GPS_VERSION.to_vec().into(), le)?);
+        }
+
+        // TIFF requires entries sorted by tag
+        entries.sort_by_key(|e| (e.kind as u8, e.ifd.tag));
+        let mut exif = ExifData::new(self.mime, entries, le);
+        // Adds the sub-IFD pointers; the serializer fills in the offsets
+        exif.refresh();
+        Ok(exif)
+    }
+}
+
+/// GPSVersionID written when a GPS IFD is created
+pub(crate) const GPS_VERSION: [u8; 4] = [2, 3, 0, 0];
+
+pub(crate) fn is_pointer(tag: ExifTag) -> bool {
+    matches!(tag, ExifTag::ExifOffset | ExifTag::GPSOffset | ExifTag::InteropOffset)
+}
+
+/// Degrees, minutes and seconds, to the millisecond of arc
+fn dms(degrees: f64) -> TagValue {
+    let total = (degrees.abs() * 3_600_000.0).round() as u32;
+    TagValue::URational(vec![
+        URational { numerator: total / 3_600_000, denominator: 1 },
+        URational { numerator: total / 60_000 % 60, denominator: 1 },
+        URational { numerator: total % 60_000, denominator: 1000 },
+    ])
+}
@@ -0,0 +1,199 @@
+use super::types::*;
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Date and time of DateTime, DateTimeOriginal or DateTimeDigitized, combined with
+/// the matching SubSecTime and OffsetTime tags.
+///
+/// The time is the local time of the camera. Without a UTC offset it is not tied
+/// to a point in time, so `to_system_time()` needs `offset` to be known.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ExifDateTime {
+    pub year: u16,
+    /// 1 to 12
+    pub month: u8,
+    /// 1 to 31
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    /// 0 to 60, allowing for a leap second
+    pub second: u8,
+    /// Fraction of the second, from SubSecTime
+    pub nanosecond: u32,
+    /// Minutes east of UTC, from OffsetTime
+    pub offset: Option<i16>,
+}
+
+impl ExifDateTime {
+    /// Parse a "YYYY:MM:DD HH:MM:SS" string, plus optional SubSecTime ("123") and
+    /// OffsetTime ("+01:00") strings.
+    ///
+    /// Dates separated by "-" or "/" are accepted, and so are blank time fields,
+    /// which read as zero. Returns `None` if the date is blank or all zeros
+    /// ("0000:00:00"), or otherwise out of range. A malformed sub-second or offset
+    /// string is ignored.
+    pub fn parse(datetime: &str, subsec: Option<&str>, offset: Option<&str>) -> Option<Self> {
+        let datetime = datetime.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+        let (date, time) = match datetime.find([' ', 'T']) {
+            Some(i) => (&datetime[..i], &datetime[i + 1..]),
+            None => (datetime, ""),
+        };
+
+        let mut date = date.split([':', '-', '/']).map(|f| f.trim().parse::<u16>().ok());
+        let year = date.next()??;
+        let month = u8::try_from(date.next()??).ok()?;
+        let day = u8::try_from(date.next()??).ok()?;
+        if date.next().is_some() || year == 0 || !valid_date(year, month.into(), day.into()) {
+            return None;
+        }
+
+        let mut time = time.split(':').map(|f| match f.trim() {
+            "" => Some(0),
+            f => f.parse::<u8>().ok(),
+        });
+        let hour = time.next().unwrap_or(Some(0))?;
+        let minute = time.next().unwrap_or(Some(0))?;
+        let second = time.next().unwrap_or(Some(0))?;
+        if time.next().is_some() || hour > 23 || minute > 59 || second > 60 {
+            return None;
+        }
+
+        Some(ExifDateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            nanosecond: subsec.map_or(0, parse_subsec),
+            offset: offset.and_then(parse_offset),
+        })
+    }
+
+    /// Point in time this date refers to, or `None` if the UTC offset is unknown.
+    ///
+    /// To assume an offset for images that lack one, set it first, e.g.
+    /// `ExifDateTime { offset: Some(0), ..dt }` for UTC.
+    pub fn to_system_time(&self) -> Option<SystemTime> {
+        let seconds = i64::from(self.hour) * 3600 + i64::from(self.minute) * 60 + i64::from(self.second)
+            - i64::from(self.offset?) * 60;
+        system_time(self.year, self.month.into(), self.day.into(), seconds, self.nanosecond)
+    }
+
+    /// Date and time without the UTC offset
+    #[cfg(feature = "chrono")]
+    pub fn to_naive_date_time(&self) -> Option<chrono::NaiveDateTime> {
+        chrono::NaiveDate::from_ymd_opt(self.year.into(), self.month.into(), self.day.into())?
+            .and_hms_nano_opt(self.hour.into(), self.minute.into(), self.second.into(), self.nanosecond)
+    }
+
+    /// Date and time with the UTC offset, or `None` if the offset is unknown
+    #[cfg(feature = "chrono")]
+    pub fn to_date_time(&self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        let offset = chrono::FixedOffset::east_opt(i32::from(self.offset?) * 60)?;
+        self.to_naive_date_time()?.and_local_timezone(offset).single()
+    }
+}
+
+/// ISO 8601 format, e.g. "2008-10-23T14:45:20.91+02:00"
+impl fmt::Display for ExifDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+               self.year, self.month, self.day, self.hour, self.minute, self.second)?;
+        if self.nanosecond > 0 {
+            let fraction = format!("{:09}", self.nanosecond);
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        if let Some(offset) = self.offset {
+            let sign = if offset < 0 { '-' } else { '+' };
+            let offset = offset.unsigned_abs();
+            write!(f, "{}{:02}:{:02}", sign, offset / 60, offset % 60)?;
+        }
+        Ok(())
+    }
+}
+
+impl ExifData {
+    /// DateTime, the date the image file was last changed
+    pub fn date_time(&self) -> Option<ExifDateTime> {
+        self.exif_date_time(ExifTag::DateTime, ExifTag::SubSecTime, ExifTag::OffsetTime)
+    }
+
+    /// DateTimeOriginal, the date the picture was taken
+    pub fn date_time_original(&self) -> Option<ExifDateTime> {
+        self.exif_date_time(ExifTag::DateTimeOriginal, ExifTag::SubSecTimeOriginal, ExifTag::OffsetTimeOriginal)
+    }
+
+    /// DateTimeDigitized, the date the image was stored as digital data
+    pub fn date_time_digitized(&self) -> Option<ExifDateTime> {
+        self.exif_date_time(ExifTag::DateTimeDigitized, ExifTag::SubSecTimeDigitized, ExifTag::OffsetTimeDigitized)
+    }
+
+    fn exif_date_time(&self, datetime: ExifTag, subsec: ExifTag, offset: ExifTag) -> Option<ExifDateTime> {
+        ExifDateTime::parse(self.get_str(datetime)?, self.get_str(subsec), self.get_str(offset))
+    }
+}
+
+/// Digits of a decimal fraction, e.g. "91" for .91 seconds
+fn parse_subsec(subsec: &str) -> u32 {
+    let digits = subsec.trim().bytes().take_while(u8::is_ascii_digit).take(9);
+    let (nanos, len) = digits.fold((0, 0), |(n, len), d| (n * 10 + u32::from(d - b'0'), len + 1));
+    nanos * 10u32.pow(9 - len)
+}
+
+/// "+HH:MM" or "-HH:MM", in minutes
+fn parse_offset(offset: &str) -> Option<i16> {
+    let offset = offset.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+    if offset == "Z" {
+        return Some(0);
+    }
+    let (sign, offset) = match offset.as_bytes().first()? {
+        b'+' => (1, &offset[1..]),
+        b'-' => (-1, &offset[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = offset.split_once(':').unwrap_or((offset, "0"));
+    let hours: i16 = hours.trim().parse().ok()?;
+    let minutes: i16 = minutes.trim().parse().ok()?;
+    if !(0..24).contains(&hours) || !(0..60).contains(&minutes) {
+        return None;
+    }
+    Some(sign * (hours * 60 + minutes))
+}
+
+/// Point in time of a UTC calendar date plus some seconds and nanoseconds into that day,
+/// or `None` if it is out of the range of `SystemTime` on this platform
+pub(crate) fn system_time(year: u16, month: u32, day: u32, seconds: i64, nanos: u32) -> Option<SystemTime> {
+    if !valid_date(year, month, day) {
+        return None;
+    }
+    let secs = days_from_civil(year.into(), month, day).checked_mul(86400)?.checked_add(seconds)?;
+    if secs >= 0 {
+        UNIX_EPOCH.checked_add(Duration::new(secs as u64, nanos))
+    } else {
+        UNIX_EPOCH.checked_sub(Duration::from_secs(secs.unsigned_abs()))?.checked_add(Duration::from_nanos(nanos.into()))
+    }
+}
+
+/// Whether the day exists in that month, counting leap years
+fn valid_date(year: u16, month: u32, day: u32) -> bool {
+    if !(1..=12).contains(&month) || day == 0 {
+        return false;
+    }
+    let (next_year, next_month) = if month == 12 { (i64::from(year) + 1, 1) } else { (i64::from(year), month + 1) };
+    let days_in_month = days_from_civil(next_year, next_month, 1) - days_from_civil(year.into(), month, 1);
+    i64::from(day) <= days_in_month
+}
+
+/// Days since 1970-01-01 of a date of the proleptic Gregorian calendar
+/// (Howard Hinnant's algorithm)
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
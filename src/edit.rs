@@ -0,0 +1,156 @@
+use std::borrow::Cow;
+use super::access::TagIndex;
+use super::builder::{is_pointer, GPS_VERSION};
+use super::exif::*;
+use super::exifpost::*;
+use super::ifdformat::*;
+use super::types::*;
+
+impl ExifData {
+    /// Sets a tag, replacing its entry if there is one, in the IFD of the existing
+    /// entry, or else in the IFD the standard prescribes. The raw data is encoded
+    /// from `value`, and the sub-IFD pointers are added as needed. Text that is not
+    /// ASCII, or replaces text of that type, takes the Exif 3.0 UTF-8 type.
+    ///
+    /// The pointer tags ExifOffset, GPSOffset and InteropOffset cannot be set.
+    pub fn set(&mut self, tag: ExifTag, value: TagValue) -> Result<(), ExifError> {
+        if is_pointer(tag) {
+            return Err(ExifError::InvalidTagValue { tag, reason: "Sub-IFD pointers are set on serialization".to_string() });
+        }
+        let kind = self.get(tag).map_or_else(|| tag_ifd(tag), |e| e.kind);
+        let mut entry = ExifEntry::new(kind, tag, value, self.le)?;
+        match self.entries.iter().position(|e| e.kind == kind && e.tag == tag) {
+            Some(i) => {
+                if let Some(old) = self.entries.get_mut(i) {
+                    // ASCII text fits the UTF-8 type of the entry it replaces
+                    if old.ifd.format == IfdFormat::Utf8 && entry.ifd.format == IfdFormat::Ascii {
+                        entry.ifd.format = IfdFormat::Utf8;
+                    }
+                    *old = entry;
+                }
+            },
+            None => {
+                // The GPS IFD must start with its version
+                if kind == IfdKind::Gps && tag != ExifTag::GPSVersionID && !self.entries.iter().any(|e| e.kind == kind) {
+                    let version = ExifEntry::new(kind, ExifTag::GPSVersionID, GPS_VERSION.to_vec().into(), self.le)?;
+                    self.insert_sorted(version);
+                }
+                self.insert_sorted(entry);
+            },
+        }
+        self.refresh();
+        Ok(())
+    }
+
+    /// Removes the entries of a tag, in any IFD, and returns them
+    pub fn remove(&mut self, tag: ExifTag) -> Vec<ExifEntry> {
+        let (removed, kept) = self.entries.drain(..).partition(|e| e.tag == tag);
+        self.entries = kept;
+        self.refresh();
+        removed
+    }
+
+    /// Keeps only the entries for which `f` returns true. Sub-IFDs left empty are
+    /// dropped together with their pointer tags.
+    pub fn retain<F: FnMut(&ExifEntry) -> bool>(&mut self, f: F) {
+        self.entries.retain(f);
+        self.refresh();
+    }
+
+    /// Converts the entries to another byte order. Values whose format depends on
+    /// the byte order are re-encoded from their `TagValue`; bytes, strings and
+    /// undefined values, such as the MakerNote, are kept as they are.
+    ///
+    /// Fails, and changes nothing, if the byte order changes and an entry has an
+    /// unknown format, or a multi-byte value that could not be parsed, since there
+    /// is no telling how to convert its bytes.
+    pub fn set_byte_order(&mut self, order: ByteOrder) -> Result<(), ExifError> {
+        let le = order.is_le();
+        if le == self.le {
+            return Ok(());
+        }
+        let mut entries = self.entries.clone();
+        for e in &mut entries {
+            match e.ifd.format {
+                IfdFormat::Unknown => return Err(ExifError::UnknownFormat { kind: e.kind, tag: e.ifd.tag }),
+                IfdFormat::U8 | IfdFormat::I8 | IfdFormat::Ascii | IfdFormat::Utf8 | IfdFormat::Undefined => {},
+                _ if matches!(e.value, TagValue::Invalid(..)) => {
+                    return Err(ExifError::UnknownFormat { kind: e.kind, tag: e.ifd.tag });
+                },
+                _ => {
+                    let (format, count, mut data) = tag_value_encode(&e.value, le);
+                    if format != e.ifd.format || count != e.ifd.count {
+                        return Err(ExifError::InvalidTagValue {
+                            tag: e.tag,
+                            reason: "Value does not match the raw data".to_string(),
+                        });
+                    }
+                    // Like IfdEntry::new(); the offset is only known once serialized
+                    if data.len() <= 4 {
+                        data.resize(4, 0);
+                        e.ifd.ifd_data = data.clone();
+                        e.ifd.ext_data = Vec::new();
+                    } else {
+                        e.ifd.ifd_data = vec![0; 4];
+                        e.ifd.ext_data = data.clone();
+                    }
+                    e.ifd.data = data;
+                },
+            }
+            if let TagValue::Undefined(_, ref mut value_le) = e.value {
+                *value_le = le;
+            }
+            e.ifd.le = le;
+        }
+        self.entries = entries;
+        self.le = le;
+        self.refresh();
+        Ok(())
+    }
+
+    /// Brings everything that depends on the set of entries up to date: sub-IFD
+    /// pointers, readable strings and the index of `get()`.
+    pub(crate) fn refresh(&mut self) {
+        self.sync_pointers();
+        for e in &mut self.entries {
+            let (_, unit, _, _, _, more_readable) = tag_to_exif(e.kind, e.ifd.tag);
+            e.unit = unit.into();
+            e.value_more_readable = more_readable(&e.value).unwrap_or(Cow::Borrowed(""));
+        }
+        exif_postprocessing_all(&mut self.entries);
+        self.index = TagIndex::new(&self.entries);
+    }
+
+    /// Adds the pointer to each sub-IFD that has entries, and removes the others
+    fn sync_pointers(&mut self) {
+        let pointers = [
+            (IfdKind::Interoperability, IfdKind::Exif, ExifTag::InteropOffset),
+            (IfdKind::Exif, IfdKind::Ifd0, ExifTag::ExifOffset),
+            (IfdKind::Gps, IfdKind::Ifd0, ExifTag::GPSOffset),
+        ];
+        // The Interoperability IFD first, since its pointer is in the Exif IFD
+        for &(kind, parent, tag) in &pointers {
+            let used = self.entries.iter().any(|e| e.kind == kind);
+            let pointer = self.entries.iter().position(|e| e.kind == parent && e.tag == tag);
+            match (used, pointer) {
+                (true, None) => {
+                    if let Ok(entry) = ExifEntry::new(parent, tag, 0u32.into(), self.le) {
+                        self.insert_sorted(entry);
+                    }
+                },
+                (false, Some(_)) => self.entries.retain(|e| !(e.kind == parent && e.tag == tag)),
+                _ => {},
+            }
+        }
+    }
+
+    /// Inserts the entry after the entries of its IFD with a lower tag number
+    fn insert_sorted(&mut self, entry: ExifEntry) {
+        let pos = self.entries.iter()
+            .rposition(|e| e.kind == entry.kind && e.ifd.tag <= entry.ifd.tag)
+            .map(|i| i + 1)
+            .or_else(|| self.entries.iter().position(|e| e.kind == entry.kind))
+            .unwrap_or(self.entries.len());
+        self.entries.insert(pos, entry);
+    }
+}
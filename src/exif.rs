@@ -4,15 +4,228 @@ use super::types::*;
 
 type ReadableFn = fn(&TagValue) -> Option<Cow<'static, str>>;
 
+type TagInfo = (ExifTag, &'static str, IfdFormat, i32, i32, ReadableFn);
+
 /// Convert a numeric tag into ExifTag enumeration, and yields information about the tag. This information
 /// is used by the main body of the parser to sanity-check the tags found in image
 /// and make sure that EXIF tags have the right data types
 ///
+/// Tag numbers are only unique within an IFD: e.g. 0x0001 is GPSLatitudeRef in the GPS IFD
+/// but InteroperabilityIndex in the Interoperability IFD, so the IFD kind selects the table.
+///
 /// Returns (tag, unit, format, min_count, max_count, more_readable)
-pub fn tag_to_exif(f: u16) -> (ExifTag, &'static str, IfdFormat, i32, i32, ReadableFn)
+pub fn tag_to_exif(kind: IfdKind, f: u16) -> TagInfo
+{
+    match kind {
+        IfdKind::Ifd0 | IfdKind::Ifd1 | IfdKind::Exif => main_tag_to_exif(f),
+        IfdKind::Gps => gps_tag_to_exif(f),
+        IfdKind::Interoperability => interop_tag_to_exif(f),
+        // Manufacturer-specific tags are not parsed (yet)
+        IfdKind::Makernote => unknown_tag(),
+    }
+}
+
+fn unknown_tag() -> TagInfo {
+    (ExifTag::UnknownToMe, "Unknown unit", IfdFormat::Unknown, -1i32, -1i32, nop)
+}
+
+/// Tags of IFD0, IFD1 and Exif IFD
+fn main_tag_to_exif(f: u16) -> TagInfo
 {
     match f {
 
+        0x00fe =>
+        (ExifTag::NewSubfileType, "none", IfdFormat::U32, 1, 1, new_subfile_type),
+
+        0x00ff =>
+        (ExifTag::SubfileType, "none", IfdFormat::U16, 1, 1, subfile_type),
+
+        0x0100 =>
+        (ExifTag::ImageWidth, "px", IfdFormat::U32, 1, 1, strpass),
+
+        0x0101 =>
+        (ExifTag::ImageLength, "px", IfdFormat::U32, 1, 1, strpass),
+
+        0x0102 =>
+        (ExifTag::BitsPerSample, "bits", IfdFormat::U16, 1, i32::MAX, strpass),
+
+        0x0103 =>
+        (ExifTag::Compression, "none", IfdFormat::U16, 1, 1, compression),
+
+        0x0106 =>
+        (ExifTag::PhotometricInterpretation, "none",
+        IfdFormat::U16, 1, 1, photometric_interpretation),
+
+        0x0107 =>
+        (ExifTag::Thresholding, "none", IfdFormat::U16, 1, 1, thresholding),
+
+        0x0108 =>
+        (ExifTag::CellWidth, "px", IfdFormat::U16, 1, 1, strpass),
+
+        0x0109 =>
+        (ExifTag::CellLength, "px", IfdFormat::U16, 1, 1, strpass),
+
+        0x010a =>
+        (ExifTag::FillOrder, "none", IfdFormat::U16, 1, 1, fill_order),
+
+        0x010d =>
+        (ExifTag::DocumentName, "none", IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0x0111 =>
+        (ExifTag::StripOffsets, "byte offset",
+        IfdFormat::U32, 1, i32::MAX, strpass),
+
+        0x0115 =>
+        (ExifTag::SamplesPerPixel, "none", IfdFormat::U16, 1, 1, strpass),
+
+        0x0116 =>
+        (ExifTag::RowsPerStrip, "rows", IfdFormat::U32, 1, 1, strpass),
+
+        0x0117 =>
+        (ExifTag::StripByteCounts, "bytes",
+        IfdFormat::U32, 1, i32::MAX, strpass),
+
+        0x0118 =>
+        (ExifTag::MinSampleValue, "none", IfdFormat::U16, 1, i32::MAX, strpass),
+
+        0x0119 =>
+        (ExifTag::MaxSampleValue, "none", IfdFormat::U16, 1, i32::MAX, strpass),
+
+        0x011c =>
+        (ExifTag::PlanarConfiguration, "none",
+        IfdFormat::U16, 1, 1, planar_configuration),
+
+        0x011d =>
+        (ExifTag::PageName, "none", IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0x011e =>
+        (ExifTag::XPosition, "@ResolutionUnit",
+        IfdFormat::URational, 1, 1, rational_value),
+
+        0x011f =>
+        (ExifTag::YPosition, "@ResolutionUnit",
+        IfdFormat::URational, 1, 1, rational_value),
+
+        0x0120 =>
+        (ExifTag::FreeOffsets, "byte offset",
+        IfdFormat::U32, 1, i32::MAX, strpass),
+
+        0x0121 =>
+        (ExifTag::FreeByteCounts, "bytes",
+        IfdFormat::U32, 1, i32::MAX, strpass),
+
+        0x0122 =>
+        (ExifTag::GrayResponseUnit, "none",
+        IfdFormat::U16, 1, 1, gray_response_unit),
+
+        0x0123 =>
+        (ExifTag::GrayResponseCurve, "@GrayResponseUnit",
+        IfdFormat::U16, 1, i32::MAX, table_values),
+
+        0x0124 =>
+        (ExifTag::T4Options, "none", IfdFormat::U32, 1, 1, strpass),
+
+        0x0125 =>
+        (ExifTag::T6Options, "none", IfdFormat::U32, 1, 1, strpass),
+
+        0x0129 =>
+        (ExifTag::PageNumber, "none", IfdFormat::U16, 2, 2, page_number),
+
+        0x012d =>
+        (ExifTag::TransferFunction, "none",
+        IfdFormat::U16, 1, i32::MAX, table_values),
+
+        0x013b =>
+        (ExifTag::Artist, "none", IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0x013d =>
+        (ExifTag::Predictor, "none", IfdFormat::U16, 1, 1, predictor),
+
+        0x0140 =>
+        (ExifTag::ColorMap, "none", IfdFormat::U16, 3, i32::MAX, table_values),
+
+        0x0141 =>
+        (ExifTag::HalftoneHints, "none", IfdFormat::U16, 2, 2, strpass),
+
+        0x0142 =>
+        (ExifTag::TileWidth, "px", IfdFormat::U32, 1, 1, strpass),
+
+        0x0143 =>
+        (ExifTag::TileLength, "px", IfdFormat::U32, 1, 1, strpass),
+
+        0x0144 =>
+        (ExifTag::TileOffsets, "byte offset",
+        IfdFormat::U32, 1, i32::MAX, strpass),
+
+        0x0145 =>
+        (ExifTag::TileByteCounts, "bytes",
+        IfdFormat::U32, 1, i32::MAX, strpass),
+
+        0x014a =>
+        (ExifTag::SubIFDs, "byte offset", IfdFormat::U32, 1, i32::MAX, strpass),
+
+        0x014c =>
+        (ExifTag::InkSet, "none", IfdFormat::U16, 1, 1, ink_set),
+
+        0x014d =>
+        (ExifTag::InkNames, "none", IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0x014e =>
+        (ExifTag::NumberOfInks, "none", IfdFormat::U16, 1, 1, strpass),
+
+        0x0150 =>
+        (ExifTag::DotRange, "none", IfdFormat::U16, 2, i32::MAX, strpass),
+
+        0x0151 =>
+        (ExifTag::TargetPrinter, "none", IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0x0152 =>
+        (ExifTag::ExtraSamples, "none",
+        IfdFormat::U16, 1, i32::MAX, extra_samples),
+
+        0x0153 =>
+        (ExifTag::SampleFormat, "none",
+        IfdFormat::U16, 1, i32::MAX, sample_format),
+
+        0x0156 =>
+        (ExifTag::TransferRange, "none", IfdFormat::U16, 6, 6, strpass),
+
+        0x015b =>
+        (ExifTag::JPEGTables, "none",
+        IfdFormat::Undefined, -1i32, -1i32, undefined_as_blob),
+
+        0x0200 =>
+        (ExifTag::JPEGProc, "none", IfdFormat::U16, 1, 1, jpeg_proc),
+
+        0x0201 =>
+        (ExifTag::JPEGInterchangeFormat, "byte offset",
+        IfdFormat::U32, 1, 1, strpass),
+
+        0x0202 =>
+        (ExifTag::JPEGInterchangeFormatLength, "bytes",
+        IfdFormat::U32, 1, 1, strpass),
+
+        0x0203 =>
+        (ExifTag::JPEGRestartInterval, "none", IfdFormat::U16, 1, 1, strpass),
+
+        0x0212 =>
+        (ExifTag::YCbCrSubSampling, "none",
+        IfdFormat::U16, 2, 2, ycbcr_subsampling),
+
+        0x0213 =>
+        (ExifTag::YCbCrPositioning, "none",
+        IfdFormat::U16, 1, 1, ycbcr_positioning),
+
+        0x02bc =>
+        (ExifTag::XMLPacket, "none", IfdFormat::U8, 1, i32::MAX, xmp_packet),
+
+        0x800d =>
+        (ExifTag::ImageID, "none", IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0x8773 =>
+        (ExifTag::InterColorProfile, "none",
+        IfdFormat::Undefined, -1i32, -1i32, undefined_as_blob),
+
         0x010e =>
         (ExifTag::ImageDescription, "none", IfdFormat::Ascii,
         -1i32, -1i32, strpass),
@@ -93,6 +306,26 @@ pub fn tag_to_exif(f: u16) -> (ExifTag, &'static str, IfdFormat, i32, i32, Reada
         (ExifTag::SensitivityType, "none",
         IfdFormat::U16, 1, 1, sensitivity_type),
 
+        0x8831 =>
+        (ExifTag::StandardOutputSensitivity, "ISO",
+        IfdFormat::U32, 1, 1, iso_speed),
+
+        0x8832 =>
+        (ExifTag::RecommendedExposureIndex, "ISO",
+        IfdFormat::U32, 1, 1, iso_speed),
+
+        0x8833 =>
+        (ExifTag::ISOSpeed, "ISO",
+        IfdFormat::U32, 1, 1, iso_speed),
+
+        0x8834 =>
+        (ExifTag::ISOSpeedLatitudeyyy, "ISO",
+        IfdFormat::U32, 1, 1, iso_speed),
+
+        0x8835 =>
+        (ExifTag::ISOSpeedLatitudezzz, "ISO",
+        IfdFormat::U32, 1, 1, iso_speed),
+
         0x8827 =>
         (ExifTag::ISOSpeedRatings, "ISO",
         IfdFormat::U16, 1, 3, iso_speeds),
@@ -113,6 +346,18 @@ pub fn tag_to_exif(f: u16) -> (ExifTag, &'static str, IfdFormat, i32, i32, Reada
         (ExifTag::DateTimeDigitized, "none",
         IfdFormat::Ascii, -1i32, -1i32, strpass),
 
+        0x9010 =>
+        (ExifTag::OffsetTime, "none",
+        IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0x9011 =>
+        (ExifTag::OffsetTimeOriginal, "none",
+        IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0x9012 =>
+        (ExifTag::OffsetTimeDigitized, "none",
+        IfdFormat::Ascii, -1i32, -1i32, strpass),
+
         0x9201 =>
         (ExifTag::ShutterSpeedValue, "APEX",
         IfdFormat::IRational, 1, 1, apex_tv),
@@ -164,6 +409,18 @@ pub fn tag_to_exif(f: u16) -> (ExifTag, &'static str, IfdFormat, i32, i32, Reada
         (ExifTag::UserComment, "none",
         IfdFormat::Undefined, -1i32, -1i32, undefined_as_encoded_string),
 
+        0x9290 =>
+        (ExifTag::SubSecTime, "none",
+        IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0x9291 =>
+        (ExifTag::SubSecTimeOriginal, "none",
+        IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0x9292 =>
+        (ExifTag::SubSecTimeDigitized, "none",
+        IfdFormat::Ascii, -1i32, -1i32, strpass),
+
         0xa000 =>
         (ExifTag::FlashPixVersion, "none",
         IfdFormat::Undefined, -1i32, -1i32, undefined_as_ascii),
@@ -256,6 +513,14 @@ pub fn tag_to_exif(f: u16) -> (ExifTag, &'static str, IfdFormat, i32, i32, Reada
         (ExifTag::Sharpness, "none",
         IfdFormat::U16, 1, 1, sharpness),
 
+        0xa430 =>
+        (ExifTag::CameraOwnerName, "none",
+        IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0xa431 =>
+        (ExifTag::BodySerialNumber, "none",
+        IfdFormat::Ascii, -1i32, -1i32, strpass),
+
         0xa432 =>
         (ExifTag::LensSpecification, "none",
         IfdFormat::URational, 4, 4, lens_spec),
@@ -268,6 +533,10 @@ pub fn tag_to_exif(f: u16) -> (ExifTag, &'static str, IfdFormat, i32, i32, Reada
         (ExifTag::LensModel, "none",
         IfdFormat::Ascii, -1i32, -1i32, strpass),
 
+        0xa435 =>
+        (ExifTag::LensSerialNumber, "none",
+        IfdFormat::Ascii, -1i32, -1i32, strpass),
+
         0xa500 =>
         (ExifTag::Gamma, "none",
         IfdFormat::URational, 1, 1, rational_value),
@@ -285,6 +554,407 @@ pub fn tag_to_exif(f: u16) -> (ExifTag, &'static str, IfdFormat, i32, i32, Reada
         (ExifTag::ImageUniqueID, "none",
         IfdFormat::Ascii, -1i32, -1i32, strpass),
 
+        0xc612 =>
+        (ExifTag::DNGVersion, "none", IfdFormat::U8, 4, 4, dng_version),
+
+        0xc613 =>
+        (ExifTag::DNGBackwardVersion, "none", IfdFormat::U8, 4, 4, dng_version),
+
+        0xc614 =>
+        (ExifTag::UniqueCameraModel, "none", IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0xc615 =>
+        (ExifTag::LocalizedCameraModel, "none",
+        IfdFormat::Ascii, -1i32, -1i32, byte_string),
+
+        0xc616 =>
+        (ExifTag::CFAPlaneColor, "none", IfdFormat::U8, 1, i32::MAX, cfa_plane_color),
+
+        0xc617 =>
+        (ExifTag::CFALayout, "none", IfdFormat::U16, 1, 1, cfa_layout),
+
+        0xc618 =>
+        (ExifTag::LinearizationTable, "none", IfdFormat::U16, 1, i32::MAX, table_values),
+
+        0xc619 =>
+        (ExifTag::BlackLevelRepeatDim, "rows, columns", IfdFormat::U16, 2, 2, strpass),
+
+        0xc61a =>
+        (ExifTag::BlackLevel, "none", IfdFormat::URational, 1, i32::MAX, decimal_values),
+
+        0xc61b =>
+        (ExifTag::BlackLevelDeltaH, "none",
+        IfdFormat::IRational, 1, i32::MAX, table_values),
+
+        0xc61c =>
+        (ExifTag::BlackLevelDeltaV, "none",
+        IfdFormat::IRational, 1, i32::MAX, table_values),
+
+        0xc61d =>
+        (ExifTag::WhiteLevel, "none", IfdFormat::U32, 1, i32::MAX, strpass),
+
+        0xc61e =>
+        (ExifTag::DefaultScale, "none", IfdFormat::URational, 2, 2, decimal_values),
+
+        0xc61f =>
+        (ExifTag::DefaultCropOrigin, "px", IfdFormat::U32, 2, 2, decimal_values),
+
+        0xc620 =>
+        (ExifTag::DefaultCropSize, "px", IfdFormat::U32, 2, 2, decimal_values),
+
+        0xc621 =>
+        (ExifTag::ColorMatrix1, "none",
+        IfdFormat::IRational, 3, i32::MAX, matrix_3_columns),
+
+        0xc622 =>
+        (ExifTag::ColorMatrix2, "none",
+        IfdFormat::IRational, 3, i32::MAX, matrix_3_columns),
+
+        0xc623 =>
+        (ExifTag::CameraCalibration1, "none",
+        IfdFormat::IRational, 1, i32::MAX, matrix_square),
+
+        0xc624 =>
+        (ExifTag::CameraCalibration2, "none",
+        IfdFormat::IRational, 1, i32::MAX, matrix_square),
+
+        0xc625 =>
+        (ExifTag::ReductionMatrix1, "none",
+        IfdFormat::IRational, 3, i32::MAX, matrix_3_rows),
+
+        0xc626 =>
+        (ExifTag::ReductionMatrix2, "none",
+        IfdFormat::IRational, 3, i32::MAX, matrix_3_rows),
+
+        0xc627 =>
+        (ExifTag::AnalogBalance, "none",
+        IfdFormat::URational, 1, i32::MAX, decimal_values),
+
+        0xc628 =>
+        (ExifTag::AsShotNeutral, "none",
+        IfdFormat::URational, 1, i32::MAX, decimal_values),
+
+        0xc629 =>
+        (ExifTag::AsShotWhiteXY, "CIE 1931 coordinates",
+        IfdFormat::URational, 2, 2, rational_values),
+
+        0xc62a =>
+        (ExifTag::BaselineExposure, "EV", IfdFormat::IRational, 1, 1, ev_offset),
+
+        0xc62b =>
+        (ExifTag::BaselineNoise, "none", IfdFormat::URational, 1, 1, rational_value),
+
+        0xc62c =>
+        (ExifTag::BaselineSharpness, "none", IfdFormat::URational, 1, 1, rational_value),
+
+        0xc62d =>
+        (ExifTag::BayerGreenSplit, "none", IfdFormat::U32, 1, 1, strpass),
+
+        0xc62e =>
+        (ExifTag::LinearResponseLimit, "none",
+        IfdFormat::URational, 1, 1, rational_value),
+
+        0xc62f =>
+        (ExifTag::CameraSerialNumber, "none", IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0xc630 =>
+        (ExifTag::LensInfo, "none", IfdFormat::URational, 4, 4, lens_spec),
+
+        0xc631 =>
+        (ExifTag::ChromaBlurRadius, "px", IfdFormat::URational, 1, 1, rational_value),
+
+        0xc632 =>
+        (ExifTag::AntiAliasStrength, "none", IfdFormat::URational, 1, 1, rational_value),
+
+        0xc633 =>
+        (ExifTag::ShadowScale, "none", IfdFormat::URational, 1, 1, rational_value),
+
+        0xc634 =>
+        (ExifTag::DNGPrivateData, "none", IfdFormat::U8, 1, i32::MAX, byte_blob),
+
+        0xc635 =>
+        (ExifTag::MakerNoteSafety, "none", IfdFormat::U16, 1, 1, maker_note_safety),
+
+        0xc65a =>
+        (ExifTag::CalibrationIlluminant1, "none", IfdFormat::U16, 1, 1, light_source),
+
+        0xc65b =>
+        (ExifTag::CalibrationIlluminant2, "none", IfdFormat::U16, 1, 1, light_source),
+
+        0xc65c =>
+        (ExifTag::BestQualityScale, "none", IfdFormat::URational, 1, 1, rational_value),
+
+        0xc65d =>
+        (ExifTag::RawDataUniqueID, "none", IfdFormat::U8, 16, 16, hex_digest),
+
+        0xc68b =>
+        (ExifTag::OriginalRawFileName, "none",
+        IfdFormat::Ascii, -1i32, -1i32, byte_string),
+
+        0xc68c =>
+        (ExifTag::OriginalRawFileData, "none",
+        IfdFormat::Undefined, -1i32, -1i32, undefined_as_blob),
+
+        0xc68d =>
+        (ExifTag::ActiveArea, "px", IfdFormat::U32, 4, 4, active_area),
+
+        0xc68e =>
+        (ExifTag::MaskedAreas, "px", IfdFormat::U32, 4, i32::MAX, strpass),
+
+        0xc68f =>
+        (ExifTag::AsShotICCProfile, "none",
+        IfdFormat::Undefined, -1i32, -1i32, undefined_as_blob),
+
+        0xc690 =>
+        (ExifTag::AsShotPreProfileMatrix, "none",
+        IfdFormat::IRational, 1, i32::MAX, matrix_3_rows),
+
+        0xc691 =>
+        (ExifTag::CurrentICCProfile, "none",
+        IfdFormat::Undefined, -1i32, -1i32, undefined_as_blob),
+
+        0xc692 =>
+        (ExifTag::CurrentPreProfileMatrix, "none",
+        IfdFormat::IRational, 1, i32::MAX, matrix_3_rows),
+
+        0xc6bf =>
+        (ExifTag::ColorimetricReference, "none",
+        IfdFormat::U16, 1, 1, colorimetric_reference),
+
+        0xc6f3 =>
+        (ExifTag::CameraCalibrationSignature, "none",
+        IfdFormat::Ascii, -1i32, -1i32, byte_string),
+
+        0xc6f4 =>
+        (ExifTag::ProfileCalibrationSignature, "none",
+        IfdFormat::Ascii, -1i32, -1i32, byte_string),
+
+        0xc6f5 =>
+        (ExifTag::ExtraCameraProfiles, "byte offset",
+        IfdFormat::U32, 1, i32::MAX, strpass),
+
+        0xc6f6 =>
+        (ExifTag::AsShotProfileName, "none", IfdFormat::Ascii, -1i32, -1i32, byte_string),
+
+        0xc6f7 =>
+        (ExifTag::NoiseReductionApplied, "none",
+        IfdFormat::URational, 1, 1, rational_value),
+
+        0xc6f8 =>
+        (ExifTag::ProfileName, "none", IfdFormat::Ascii, -1i32, -1i32, byte_string),
+
+        0xc6f9 =>
+        (ExifTag::ProfileHueSatMapDims, "hue, saturation, value",
+        IfdFormat::U32, 3, 3, strpass),
+
+        0xc6fa =>
+        (ExifTag::ProfileHueSatMapData1, "none",
+        IfdFormat::F32, 1, i32::MAX, table_values),
+
+        0xc6fb =>
+        (ExifTag::ProfileHueSatMapData2, "none",
+        IfdFormat::F32, 1, i32::MAX, table_values),
+
+        0xc6fc =>
+        (ExifTag::ProfileToneCurve, "none", IfdFormat::F32, 2, i32::MAX, table_values),
+
+        0xc6fd =>
+        (ExifTag::ProfileEmbedPolicy, "none", IfdFormat::U32, 1, 1, profile_embed_policy),
+
+        0xc6fe =>
+        (ExifTag::ProfileCopyright, "none", IfdFormat::Ascii, -1i32, -1i32, byte_string),
+
+        0xc714 =>
+        (ExifTag::ForwardMatrix1, "none",
+        IfdFormat::IRational, 3, i32::MAX, matrix_3_rows),
+
+        0xc715 =>
+        (ExifTag::ForwardMatrix2, "none",
+        IfdFormat::IRational, 3, i32::MAX, matrix_3_rows),
+
+        0xc716 =>
+        (ExifTag::PreviewApplicationName, "none",
+        IfdFormat::Ascii, -1i32, -1i32, byte_string),
+
+        0xc717 =>
+        (ExifTag::PreviewApplicationVersion, "none",
+        IfdFormat::Ascii, -1i32, -1i32, byte_string),
+
+        0xc718 =>
+        (ExifTag::PreviewSettingsName, "none",
+        IfdFormat::Ascii, -1i32, -1i32, byte_string),
+
+        0xc719 =>
+        (ExifTag::PreviewSettingsDigest, "none", IfdFormat::U8, 16, 16, hex_digest),
+
+        0xc71a =>
+        (ExifTag::PreviewColorSpace, "none", IfdFormat::U32, 1, 1, preview_color_space),
+
+        0xc71b =>
+        (ExifTag::PreviewDateTime, "none", IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0xc71c =>
+        (ExifTag::RawImageDigest, "none", IfdFormat::U8, 16, 16, hex_digest),
+
+        0xc71d =>
+        (ExifTag::OriginalRawFileDigest, "none", IfdFormat::U8, 16, 16, hex_digest),
+
+        0xc71e =>
+        (ExifTag::SubTileBlockSize, "px", IfdFormat::U32, 2, 2, strpass),
+
+        0xc71f =>
+        (ExifTag::RowInterleaveFactor, "rows", IfdFormat::U32, 1, 1, strpass),
+
+        0xc725 =>
+        (ExifTag::ProfileLookTableDims, "hue, saturation, value",
+        IfdFormat::U32, 3, 3, strpass),
+
+        0xc726 =>
+        (ExifTag::ProfileLookTableData, "none",
+        IfdFormat::F32, 1, i32::MAX, table_values),
+
+        0xc740 =>
+        (ExifTag::OpcodeList1, "none", IfdFormat::Undefined, -1i32, -1i32, opcode_list),
+
+        0xc741 =>
+        (ExifTag::OpcodeList2, "none", IfdFormat::Undefined, -1i32, -1i32, opcode_list),
+
+        0xc74e =>
+        (ExifTag::OpcodeList3, "none", IfdFormat::Undefined, -1i32, -1i32, opcode_list),
+
+        0xc761 =>
+        (ExifTag::NoiseProfile, "none", IfdFormat::F64, 2, i32::MAX, strpass),
+
+        0xc763 =>
+        (ExifTag::TimeCodes, "none", IfdFormat::U8, 8, i32::MAX, byte_blob),
+
+        0xc764 =>
+        (ExifTag::FrameRate, "fps", IfdFormat::IRational, 1, 1, rational_value),
+
+        0xc772 =>
+        (ExifTag::TStop, "none", IfdFormat::IRational, 1, 2, t_stop),
+
+        0xc789 =>
+        (ExifTag::ReelName, "none", IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0xc791 =>
+        (ExifTag::OriginalDefaultFinalSize, "px", IfdFormat::U32, 2, 2, strpass),
+
+        0xc792 =>
+        (ExifTag::OriginalBestQualityFinalSize, "px", IfdFormat::U32, 2, 2, strpass),
+
+        0xc793 =>
+        (ExifTag::OriginalDefaultCropSize, "px", IfdFormat::U32, 2, 2, decimal_values),
+
+        0xc7a1 =>
+        (ExifTag::CameraLabel, "none", IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0xc7a3 =>
+        (ExifTag::ProfileHueSatMapEncoding, "none",
+        IfdFormat::U32, 1, 1, profile_encoding),
+
+        0xc7a4 =>
+        (ExifTag::ProfileLookTableEncoding, "none",
+        IfdFormat::U32, 1, 1, profile_encoding),
+
+        0xc7a5 =>
+        (ExifTag::BaselineExposureOffset, "EV", IfdFormat::IRational, 1, 1, ev_offset),
+
+        0xc7a6 =>
+        (ExifTag::DefaultBlackRender, "none", IfdFormat::U32, 1, 1, default_black_render),
+
+        0xc7a7 =>
+        (ExifTag::NewRawImageDigest, "none", IfdFormat::U8, 16, 16, hex_digest),
+
+        0xc7a8 =>
+        (ExifTag::RawToPreviewGain, "none", IfdFormat::F64, 1, 1, strpass),
+
+        0xc7b5 =>
+        (ExifTag::DefaultUserCrop, "top, left, bottom, right",
+        IfdFormat::URational, 4, 4, decimal_values),
+
+        0xc7d5 =>
+        (ExifTag::DepthFormat, "none", IfdFormat::U16, 1, 1, depth_format),
+
+        0xc7d6 =>
+        (ExifTag::DepthNear, "@DepthUnits", IfdFormat::URational, 1, 1, rational_value),
+
+        0xc7d7 =>
+        (ExifTag::DepthFar, "@DepthUnits", IfdFormat::URational, 1, 1, rational_value),
+
+        0xc7d8 =>
+        (ExifTag::DepthUnits, "none", IfdFormat::U16, 1, 1, depth_units),
+
+        0xc7d9 =>
+        (ExifTag::DepthMeasureType, "none", IfdFormat::U16, 1, 1, depth_measure_type),
+
+        0xc7da =>
+        (ExifTag::EnhanceParams, "none", IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0xcd2d =>
+        (ExifTag::ProfileGainTableMap, "none",
+        IfdFormat::Undefined, -1i32, -1i32, undefined_as_blob),
+
+        0xcd2e =>
+        (ExifTag::SemanticName, "none", IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0xcd30 =>
+        (ExifTag::SemanticInstanceID, "none", IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0xcd31 =>
+        (ExifTag::CalibrationIlluminant3, "none", IfdFormat::U16, 1, 1, light_source),
+
+        0xcd32 =>
+        (ExifTag::CameraCalibration3, "none",
+        IfdFormat::IRational, 1, i32::MAX, matrix_square),
+
+        0xcd33 =>
+        (ExifTag::ColorMatrix3, "none",
+        IfdFormat::IRational, 3, i32::MAX, matrix_3_columns),
+
+        0xcd34 =>
+        (ExifTag::ForwardMatrix3, "none",
+        IfdFormat::IRational, 3, i32::MAX, matrix_3_rows),
+
+        0xcd35 =>
+        (ExifTag::IlluminantData1, "none",
+        IfdFormat::Undefined, -1i32, -1i32, undefined_as_blob),
+
+        0xcd36 =>
+        (ExifTag::IlluminantData2, "none",
+        IfdFormat::Undefined, -1i32, -1i32, undefined_as_blob),
+
+        0xcd37 =>
+        (ExifTag::IlluminantData3, "none",
+        IfdFormat::Undefined, -1i32, -1i32, undefined_as_blob),
+
+        0xcd38 =>
+        (ExifTag::MaskSubArea, "px", IfdFormat::U32, 4, 4, active_area),
+
+        0xcd39 =>
+        (ExifTag::ProfileHueSatMapData3, "none",
+        IfdFormat::F32, 1, i32::MAX, table_values),
+
+        0xcd3a =>
+        (ExifTag::ReductionMatrix3, "none",
+        IfdFormat::IRational, 3, i32::MAX, matrix_3_rows),
+
+        0xcd3b =>
+        (ExifTag::RGBTables, "none",
+        IfdFormat::Undefined, -1i32, -1i32, undefined_as_blob),
+
+        0xa005 =>
+        (ExifTag::InteropOffset, "byte offset",
+        IfdFormat::U32, 1, 1, strpass),
+
+        _ => unknown_tag(),
+    }
+}
+
+/// Tags of GPS IFD
+fn gps_tag_to_exif(f: u16) -> TagInfo
+{
+    match f {
         0x0 =>
         (ExifTag::GPSVersionID, "none",
         IfdFormat::U8, 4, 4, strpass),
@@ -405,8 +1075,97 @@ pub fn tag_to_exif(f: u16) -> (ExifTag, &'static str, IfdFormat, i32, i32, Reada
         (ExifTag::GPSDifferential, "none",
         IfdFormat::U16, 1, 1, gpsdiff),
 
-        _ =>
-        (ExifTag::UnknownToMe, "Unknown unit",
-        IfdFormat::Unknown, -1i32, -1i32, nop)
+        _ => unknown_tag(),
+    }
+}
+
+/// Tags of Interoperability IFD
+fn interop_tag_to_exif(f: u16) -> TagInfo
+{
+    match f {
+        0x0001 =>
+        (ExifTag::InteroperabilityIndex, "none",
+        IfdFormat::Ascii, -1i32, -1i32, interop_index),
+
+        0x0002 =>
+        (ExifTag::InteroperabilityVersion, "none",
+        IfdFormat::Undefined, -1i32, -1i32, undefined_as_ascii),
+
+        0x1000 =>
+        (ExifTag::RelatedImageFileFormat, "none",
+        IfdFormat::Ascii, -1i32, -1i32, strpass),
+
+        0x1001 =>
+        (ExifTag::RelatedImageWidth, "px", IfdFormat::U32, 1, 1, strpass),
+
+        0x1002 =>
+        (ExifTag::RelatedImageLength, "px", IfdFormat::U32, 1, 1, strpass),
+
+        _ => unknown_tag(),
+    }
+}
+
+/// IFD a tag belongs in, according to the Exif and DNG specifications
+pub(crate) fn tag_ifd(tag: ExifTag) -> IfdKind {
+    let code = (tag as u32) & 0xffff;
+    match (tag as u32) >> 16 {
+        0x0100 => return IfdKind::Interoperability,
+        0 => {},
+        _ => return IfdKind::Makernote,
+    }
+    match code {
+        0x0000..=0x001f => IfdKind::Gps,
+        // GPSOffset, amid the Exif tags
+        0x8825 => IfdKind::Ifd0,
+        0x829a | 0x829d | 0x8822 | 0x8824 | 0x8827..=0x8835 => IfdKind::Exif,
+        0x9000..=0xafff => IfdKind::Exif,
+        _ => IfdKind::Ifd0,
+    }
+}
+
+/// Formats that the TIFF and DNG specifications allow for a tag in addition to the
+/// one returned by `tag_to_exif`. A value stored in one of these is not a format error.
+pub(crate) fn alternative_formats(tag: ExifTag) -> &'static [IfdFormat] {
+    match tag {
+        ExifTag::ImageWidth
+        | ExifTag::ImageLength
+        | ExifTag::RowsPerStrip
+        | ExifTag::StripOffsets
+        | ExifTag::StripByteCounts
+        | ExifTag::TileWidth
+        | ExifTag::TileLength
+        | ExifTag::TileByteCounts
+        | ExifTag::WhiteLevel
+        | ExifTag::ActiveArea
+        | ExifTag::MaskedAreas
+        | ExifTag::SubTileBlockSize
+        | ExifTag::RowInterleaveFactor
+        | ExifTag::OriginalDefaultFinalSize
+        | ExifTag::OriginalBestQualityFinalSize
+        | ExifTag::RelatedImageWidth
+        | ExifTag::RelatedImageLength => &[IfdFormat::U16],
+
+        ExifTag::DefaultCropOrigin
+        | ExifTag::DefaultCropSize
+        | ExifTag::OriginalDefaultCropSize => &[IfdFormat::U16, IfdFormat::URational],
+
+        ExifTag::BlackLevel => &[IfdFormat::U16, IfdFormat::U32],
+
+        ExifTag::AsShotNeutral => &[IfdFormat::U16],
+
+        ExifTag::LocalizedCameraModel
+        | ExifTag::OriginalRawFileName
+        | ExifTag::CameraCalibrationSignature
+        | ExifTag::ProfileCalibrationSignature
+        | ExifTag::AsShotProfileName
+        | ExifTag::ProfileName
+        | ExifTag::ProfileCopyright
+        | ExifTag::PreviewApplicationName
+        | ExifTag::PreviewApplicationVersion
+        | ExifTag::PreviewSettingsName => &[IfdFormat::U8],
+
+        ExifTag::DotRange => &[IfdFormat::U8],
+
+        _ => &[],
     }
 }
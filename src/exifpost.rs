@@ -2,8 +2,18 @@ use super::types::*;
 
 /// Find a tag of given type
 fn other_tag<'a>(tag: ExifTag, entries1: &'a [ExifEntry], entries2: &'a [ExifEntry]) -> Option<&'a ExifEntry> {
-    entries1.into_iter().find(|entry| entry.tag == tag)
-    .or_else(|| entries2.into_iter().find(|entry| entry.tag == tag))
+    entries1.iter().find(|entry| entry.tag == tag)
+    .or_else(|| entries2.iter().find(|entry| entry.tag == tag))
+}
+
+/// Runs `exif_postprocessing` on every entry
+pub(crate) fn exif_postprocessing_all(entries: &mut [ExifEntry]) {
+    for n in 0..entries.len() {
+        let (begin, end) = entries.split_at_mut(n);
+        if let Some((entry, end)) = end.split_first_mut() {
+            exif_postprocessing(entry, begin, end);
+        }
+    }
 }
 
 /// Does postprocessing in tags that depend on other tags to have a complete interpretation
@@ -19,6 +29,24 @@ pub(crate) fn exif_postprocessing(entry: &mut ExifEntry, entries1: &[ExifEntry],
             }
         }
 
+        ExifTag::XPosition | ExifTag::YPosition => {
+            if let Some(f) = other_tag(ExifTag::ResolutionUnit, entries1, entries2) {
+                entry.unit = f.value_more_readable.clone();
+                let v = entry.value_more_readable.to_mut();
+                v.push(' ');
+                v.push_str(&f.value_more_readable);
+            }
+        }
+
+        ExifTag::DepthNear | ExifTag::DepthFar => {
+            if let Some(f) = other_tag(ExifTag::DepthUnits, entries1, entries2) {
+                entry.unit = f.value_more_readable.clone();
+                let v = entry.value_more_readable.to_mut();
+                v.push(' ');
+                v.push_str(&f.value_more_readable);
+            }
+        }
+
         ExifTag::FocalPlaneXResolution | ExifTag::FocalPlaneYResolution => {
             if let Some(f) = other_tag(ExifTag::FocalPlaneResolutionUnit, entries1, entries2) {
                 entry.unit = f.value_more_readable.clone();
@@ -47,7 +75,10 @@ pub(crate) fn exif_postprocessing(entry: &mut ExifEntry, entries1: &[ExifEntry],
         ExifTag::GPSAltitude => {
             if let Some(f) = other_tag(ExifTag::GPSAltitudeRef, entries1, entries2) {
                 let altref = match f.value {
-                    TagValue::U8(ref fv) => fv[0],
+                    TagValue::U8(ref fv) => match fv.first() {
+                        Some(&altref) => altref,
+                        None => return,
+                    },
                     _ => return,
                 };
 
@@ -1,5 +1,5 @@
 use super::ifdformat::*;
-use super::lowlevel::read_u16_array;
+use super::lowlevel::read_u32;
 use super::types::*;
 use std::borrow::Cow;
 
@@ -18,7 +18,7 @@ pub(crate) fn strpass(e: &TagValue) -> Option<Cow<'static, str>> {
 /// Indicates which one of the parameters of ISO12232 is used for PhotographicSensitivity
 pub(crate) fn sensitivity_type(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
-        TagValue::U16(ref v) => Some(match v.get(0)? {
+        TagValue::U16(ref v) => Some(match v.first()? {
             0 => "Unknown",
             1 => "Standard output sensitivity (SOS)",
             2 => "Recommended exposure index (REI)",
@@ -36,10 +36,14 @@ pub(crate) fn sensitivity_type(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn orientation(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 1 => "Straight",
+                2 => "Mirrored",
                 3 => "Upside down",
+                4 => "Mirrored upside down",
+                5 => "Mirrored and rotated to right",
                 6 => "Rotated to left",
+                7 => "Mirrored and rotated to left",
                 8 => "Rotated to right",
                 9 => "Undefined",
                 n => return Some(format!("Unknown ({})", n).into()),
@@ -51,8 +55,8 @@ pub(crate) fn orientation(e: &TagValue) -> Option<Cow<'static, str>> {
 
 pub(crate) fn rational_value(e: &TagValue) -> Option<Cow<'static, str>> {
     Some(match e {
-        TagValue::URational(v) => v.get(0)?.value(),
-        TagValue::IRational(v) => v.get(0)?.value(),
+        TagValue::URational(v) => v.first()?.value(),
+        TagValue::IRational(v) => v.first()?.value(),
         _ => return None,
     }.to_string().into())
 }
@@ -69,7 +73,7 @@ pub(crate) fn rational_values(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn resolution_unit(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 1 => "Unitless",
                 2 => "in",
                 3 => "cm",
@@ -83,7 +87,7 @@ pub(crate) fn resolution_unit(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn exposure_time(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::URational(ref v) => {
-            let r = v.get(0)?;
+            let r = v.first()?;
             Some(if r.numerator == 1 && r.denominator > 1 {
                 // traditional 1/x exposure time
                 format!("{} s", r)
@@ -101,7 +105,7 @@ pub(crate) fn exposure_time(e: &TagValue) -> Option<Cow<'static, str>> {
 
 pub(crate) fn f_number(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
-        TagValue::URational(ref v) => Some(format!("f/{:.1}", v.get(0)?.value()).into()),
+        TagValue::URational(ref v) => Some(format!("f/{:.1}", v.first()?.value()).into()),
         _ => None,
     }
 }
@@ -109,7 +113,7 @@ pub(crate) fn f_number(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn exposure_program(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 1 => "Manual control",
                 2 => "Program control",
                 3 => "Aperture priority",
@@ -127,21 +131,21 @@ pub(crate) fn exposure_program(e: &TagValue) -> Option<Cow<'static, str>> {
 
 pub(crate) fn focal_length(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
-        TagValue::URational(ref v) => Some(format!("{} mm", v.get(0)?.value()).into()),
+        TagValue::URational(ref v) => Some(format!("{} mm", v.first()?.value()).into()),
         _ => None,
     }
 }
 
 pub(crate) fn focal_length_35(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
-        TagValue::U16(ref v) => Some(format!("{} mm", v.get(0)?).into()),
+        TagValue::U16(ref v) => Some(format!("{} mm", v.first()?).into()),
         _ => None,
     }
 }
 
 pub(crate) fn meters(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
-        TagValue::URational(ref v) => Some(format!("{:.1} m", v.get(0)?.value()).into()),
+        TagValue::URational(ref v) => Some(format!("{:.1} m", v.first()?.value()).into()),
         _ => None,
     }
 }
@@ -149,24 +153,30 @@ pub(crate) fn meters(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn iso_speeds(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            Some(if v.len() == 1 {
-                format!("ISO {}", v[0])
-            } else if v.len() == 2 || v.len() == 3 {
-                format!("ISO {} latitude {}", v[0], v[1])
-            } else {
-                format!("Unknown ({})", NumArray::new(v))
+            Some(match **v {
+                [iso] => format!("ISO {}", iso),
+                [iso, latitude] | [iso, latitude, _] => format!("ISO {} latitude {}", iso, latitude),
+                _ => format!("Unknown ({})", NumArray::new(v)),
             }.into())
         },
         _ => None,
     }
 }
 
+pub(crate) fn iso_speed(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U32(ref v) => Some(format!("ISO {}", v.first()?).into()),
+        _ => None,
+    }
+}
+
 pub(crate) fn dms(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
-        TagValue::URational(ref v) if v.len() >= 3 => {
-            let deg = v[0];
-            let min = v[1];
-            let sec = v[2];
+        TagValue::URational(ref v) => {
+            let (deg, min, sec) = match **v {
+                [deg, min, sec, ..] => (deg, min, sec),
+                _ => return None,
+            };
             Some(if deg.denominator == 1 && min.denominator == 1 {
                 format!("{}°{}'{:.2}\"", deg.value(), min.value(), sec.value())
             } else if deg.denominator == 1 {
@@ -186,7 +196,7 @@ pub(crate) fn dms(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn gps_alt_ref(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U8(ref v) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 0 => "Above sea level",
                 1 => "Below sea level",
                 n => return Some(format!("Unknown, assumed below sea level ({})", n).into()),
@@ -215,7 +225,7 @@ pub(crate) fn gpsdestdistanceref(e: &TagValue) -> Option<Cow<'static, str>> {
 
 pub(crate) fn gpsdestdistance(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
-        TagValue::URational(ref v) => Some(format!("{:.3}", v.get(0)?.value()).into()),
+        TagValue::URational(ref v) => Some(format!("{:.3}", v.first()?.value()).into()),
         _ => None,
     }
 }
@@ -239,7 +249,7 @@ pub(crate) fn gpsspeedref(e: &TagValue) -> Option<Cow<'static, str>> {
 
 pub(crate) fn gpsspeed(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
-        TagValue::URational(ref v) => Some(format!("{:.1}", v.get(0)?.value()).into()),
+        TagValue::URational(ref v) => Some(format!("{:.1}", v.first()?.value()).into()),
         _ => None,
     }
 }
@@ -261,7 +271,7 @@ pub(crate) fn gpsbearingref(e: &TagValue) -> Option<Cow<'static, str>> {
 
 pub(crate) fn gpsbearing(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
-        TagValue::URational(ref v) => Some(format!("{:.2}°", v.get(0)?.value()).into()),
+        TagValue::URational(ref v) => Some(format!("{:.2}°", v.first()?.value()).into()),
         _ => None,
     }
 }
@@ -270,7 +280,7 @@ pub(crate) fn gpstimestamp(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::URational(ref v) => {
             let sec = v.get(2)?;
-            let hour = v.get(0)?;
+            let hour = v.first()?;
             let min = v.get(1)?;
             Some(format!(
                 "{:02.0}:{:02.0}:{:04.1} UTC",
@@ -286,7 +296,7 @@ pub(crate) fn gpstimestamp(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn gpsdiff(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 0 => "Measurement without differential correction".into(),
                 1 => "Differential correction applied".into(),
                 n => format!("Unknown ({})", n).into(),
@@ -349,27 +359,14 @@ pub(crate) fn undefined_as_u8(e: &TagValue) -> Option<Cow<'static, str>> {
 /// the string encoding /// format can be discovered by looking into the first
 /// 8 bytes.
 pub(crate) fn undefined_as_encoded_string(e: &TagValue) -> Option<Cow<'static, str>> {
-    // "ASCII\0\0\0"
-    static ASC: [u8; 8] = [0x41, 0x53, 0x43, 0x49, 0x49, 0, 0, 0];
-    // "JIS\0\0\0\0\0"
-    static JIS: [u8; 8] = [0x4a, 0x49, 0x53, 0, 0, 0, 0, 0];
-    // "UNICODE\0"
-    static UNICODE: [u8; 8] = [0x55, 0x4e, 0x49, 0x43, 0x4f, 0x44, 0x45, 0x00];
+    if let Some(t) = e.to_encoded_text() {
+        return Some(t.text.into());
+    }
 
     match *e {
-        TagValue::Undefined(ref v, le) => {
+        TagValue::Undefined(ref v, _) => {
             Some(if v.len() < 8 {
                 format!("String w/ truncated preamble {}", NumArray::new(v))
-            } else if v[0..8] == ASC[..] {
-                String::from_utf8_lossy(&v[8..]).into_owned()
-            } else if v[0..8] == JIS[..] {
-                format!("JIS string {}", NumArray::new(&v[8..]))
-            } else if v[0..8] == UNICODE[..] {
-                let v8 = &v[8..];
-                // reinterpret as vector of u16
-                let v16_size = (v8.len() / 2) as u32;
-                let v16 = read_u16_array(le, v16_size, v8)?;
-                String::from_utf16_lossy(&v16)
             } else {
                 format!("String w/ undefined encoding {}", NumArray::new(v))
             }.into())
@@ -388,14 +385,14 @@ pub(crate) fn undefined_as_blob(e: &TagValue) -> Option<Cow<'static, str>> {
 
 pub(crate) fn apex_tv(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
-        TagValue::IRational(ref v) => Some(format!("{:.1} Tv APEX", v.get(0)?.value()).into()),
+        TagValue::IRational(ref v) => Some(format!("{:.1} Tv APEX", v.first()?.value()).into()),
         _ => None,
     }
 }
 
 pub(crate) fn apex_av(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
-        TagValue::URational(ref v) => Some(format!("{:.1} Av APEX", v.get(0)?.value()).into()),
+        TagValue::URational(ref v) => Some(format!("{:.1} Av APEX", v.first()?.value()).into()),
         _ => None,
     }
 }
@@ -404,10 +401,10 @@ pub(crate) fn apex_brightness(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::IRational(ref v) => {
             // numerator 0xffffffff = unknown
-            Some(if v.get(0)?.numerator == -1 {
+            Some(if v.first()?.numerator == -1 {
                 "Unknown".into()
             } else {
-                format!("{:.1} APEX", v.get(0)?.value()).into()
+                format!("{:.1} APEX", v.first()?.value()).into()
             })
         },
         _ => None,
@@ -416,7 +413,7 @@ pub(crate) fn apex_brightness(e: &TagValue) -> Option<Cow<'static, str>> {
 
 pub(crate) fn apex_ev(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
-        TagValue::IRational(ref v) => Some(format!("{:.2} EV APEX", v.get(0)?.value()).into()),
+        TagValue::IRational(ref v) => Some(format!("{:.2} EV APEX", v.first()?.value()).into()),
         _ => None,
     }
 }
@@ -424,7 +421,7 @@ pub(crate) fn apex_ev(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn file_source(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::Undefined(ref v, _) => {
-            Some(if !v.is_empty() && v[0] == 3 {
+            Some(if v.first() == Some(&3) {
                 "DSC"
             } else {
                 "Unknown"
@@ -436,7 +433,7 @@ pub(crate) fn file_source(e: &TagValue) -> Option<Cow<'static, str>> {
 
 pub(crate) fn flash_energy(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
-        TagValue::URational(ref v) => Some(format!("{} BCPS", v.get(0)?.value()).into()),
+        TagValue::URational(ref v) => Some(format!("{} BCPS", v.first()?.value()).into()),
         _ => None,
     }
 }
@@ -444,7 +441,7 @@ pub(crate) fn flash_energy(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn metering_mode(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 0 => "Unknown",
                 1 => "Average",
                 2 => "Center-weighted average",
@@ -463,7 +460,7 @@ pub(crate) fn metering_mode(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn light_source(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 0 => "Unknown",
                 1 => "Daylight",
                 2 => "Fluorescent",
@@ -495,7 +492,7 @@ pub(crate) fn light_source(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn color_space(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 1 => "sRGB",
                 65535 => "Uncalibrated",
                 n => return Some(format!("Unknown ({})", n).into()),
@@ -508,7 +505,7 @@ pub(crate) fn color_space(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn flash(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            let n = v.get(0)?;
+            let n = v.first()?;
             let mut b0 = "Did not fire. ";
             let mut b12 = "";
             let mut b34 = "";
@@ -542,7 +539,7 @@ pub(crate) fn flash(e: &TagValue) -> Option<Cow<'static, str>> {
             } else if m == 2 {
                 b34 = "Forced suppresion. ";
             } else if m == 3 {
-                b12 = "Auto mode. ";
+                b34 = "Auto mode. ";
             }
 
             Some(format!("{}{}{}{}", b0, b12, b34, b6).into())
@@ -553,12 +550,12 @@ pub(crate) fn flash(e: &TagValue) -> Option<Cow<'static, str>> {
 
 pub(crate) fn subject_area(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
-        TagValue::U16(ref v) => Some(match v.len() {
-            2 => format!("at pixel {},{}", v[0], v[1]),
-            3 => format!("at center {},{} radius {}", v[0], v[1], v[2]),
-            4 => format!(
+        TagValue::U16(ref v) => Some(match **v {
+            [x, y] => format!("at pixel {},{}", x, y),
+            [x, y, radius] => format!("at center {},{} radius {}", x, y, radius),
+            [x, y, width, height] => format!(
                 "at rectangle {},{} width {} height {}",
-                v[0], v[1], v[2], v[3]
+                x, y, width, height
             ),
             _ => format!("Unknown ({}) ", NumArray::new(v)),
         }.into()),
@@ -568,7 +565,10 @@ pub(crate) fn subject_area(e: &TagValue) -> Option<Cow<'static, str>> {
 
 pub(crate) fn subject_location(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
-        TagValue::U16(ref v) if v.len() >= 2 => Some(format!("at pixel {},{}", v[0], v[1]).into()),
+        TagValue::U16(ref v) => match **v {
+            [x, y, ..] => Some(format!("at pixel {},{}", x, y).into()),
+            _ => None,
+        },
         _ => None,
     }
 }
@@ -576,7 +576,7 @@ pub(crate) fn subject_location(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn sharpness(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 0 => "Normal",
                 1 => "Soft",
                 2 => "Hard",
@@ -590,7 +590,7 @@ pub(crate) fn sharpness(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn saturation(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 0 => "Normal",
                 1 => "Low",
                 2 => "High",
@@ -604,7 +604,7 @@ pub(crate) fn saturation(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn contrast(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 0 => "Normal",
                 1 => "Soft",
                 2 => "Hard",
@@ -618,7 +618,7 @@ pub(crate) fn contrast(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn gain_control(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 0 => "None",
                 1 => "Low gain up",
                 2 => "High gain up",
@@ -634,7 +634,7 @@ pub(crate) fn gain_control(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn exposure_mode(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 0 => "Auto exposure",
                 1 => "Manual exposure",
                 2 => "Auto bracket",
@@ -648,7 +648,7 @@ pub(crate) fn exposure_mode(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn scene_capture_type(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 0 => "Standard",
                 1 => "Landscape",
                 2 => "Portrait",
@@ -663,7 +663,7 @@ pub(crate) fn scene_capture_type(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn scene_type(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::Undefined(ref v, _) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 1 => "Directly photographed image",
                 n => return Some(format!("Unknown ({})", n).into()),
             }.into())
@@ -675,7 +675,7 @@ pub(crate) fn scene_type(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn white_balance_mode(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 0 => "Auto",
                 1 => "Manual",
                 n => return Some(format!("Unknown ({})", n).into()),
@@ -688,7 +688,7 @@ pub(crate) fn white_balance_mode(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn sensing_method(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 1 => "Not defined",
                 2 => "One-chip color area sensor",
                 3 => "Two-chip color area sensor",
@@ -706,7 +706,7 @@ pub(crate) fn sensing_method(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn custom_rendered(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 0 => "Normal",
                 1 => "Custom",
                 n => return Some(format!("Unknown ({})", n).into()),
@@ -719,7 +719,7 @@ pub(crate) fn custom_rendered(e: &TagValue) -> Option<Cow<'static, str>> {
 pub(crate) fn subject_distance_range(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
         TagValue::U16(ref v) => {
-            Some(match v.get(0)? {
+            Some(match v.first()? {
                 0 => "Unknown",
                 1 => "Macro",
                 2 => "Close view",
@@ -733,13 +733,17 @@ pub(crate) fn subject_distance_range(e: &TagValue) -> Option<Cow<'static, str>>
 
 pub(crate) fn lens_spec(e: &TagValue) -> Option<Cow<'static, str>> {
     match *e {
-        TagValue::URational(ref v) if v.len() >= 4 => {
-            let f0 = v[0].value();
-            let f1 = v[1].value();
-            let a0 = v[2].value();
-            let a1 = v[3].value();
+        TagValue::URational(ref v) => {
+            let (min_focal, max_focal, min_f, max_f) = match **v {
+                [min_focal, max_focal, min_f, max_f, ..] => (min_focal, max_focal, min_f, max_f),
+                _ => return None,
+            };
+            let f0 = min_focal.value();
+            let f1 = max_focal.value();
+            let a0 = min_f.value();
+            let a1 = max_f.value();
 
-            Some(if v[0] == v[1] {
+            Some(if min_focal == max_focal {
                 if a0.is_finite() {
                     format!("{} mm f/{:.1}", f0, a0)
                 } else {
@@ -754,3 +758,634 @@ pub(crate) fn lens_spec(e: &TagValue) -> Option<Cow<'static, str>> {
         _ => None,
     }
 }
+
+pub(crate) fn new_subfile_type(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U32(ref v) => {
+            let n = v.first()?;
+            if *n == 0 {
+                return Some("Full-resolution image".into());
+            }
+            let mut parts = vec![];
+            if (n & 1) > 0 {
+                parts.push("Reduced-resolution image");
+            }
+            if (n & 2) > 0 {
+                parts.push("Single page of multi-page image");
+            }
+            if (n & 4) > 0 {
+                parts.push("Transparency mask");
+            }
+            if (n & !7) > 0 {
+                return Some(format!("Unknown ({})", n).into());
+            }
+            Some(parts.join(", ").into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn subfile_type(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            Some(match v.first()? {
+                1 => "Full-resolution image",
+                2 => "Reduced-resolution image",
+                3 => "Single page of multi-page image",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn compression(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            Some(match v.first()? {
+                1 => "Uncompressed",
+                2 => "CCITT modified Huffman RLE",
+                3 => "CCITT Group 3 fax",
+                4 => "CCITT Group 4 fax",
+                5 => "LZW",
+                6 => "JPEG (old-style)",
+                7 => "JPEG",
+                8 => "Deflate",
+                9 => "JBIG black and white",
+                10 => "JBIG color",
+                32773 => "PackBits",
+                32946 => "Deflate (PKZIP)",
+                34712 => "JPEG 2000",
+                34892 => "Lossy JPEG",
+                34925 => "LZMA2",
+                50000 => "Zstandard",
+                50001 => "WebP",
+                52546 => "JPEG XL",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn photometric_interpretation(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            Some(match v.first()? {
+                0 => "WhiteIsZero",
+                1 => "BlackIsZero",
+                2 => "RGB",
+                3 => "RGB palette",
+                4 => "Transparency mask",
+                5 => "CMYK",
+                6 => "YCbCr",
+                8 => "CIELab",
+                9 => "ICCLab",
+                10 => "ITULab",
+                32803 => "Color filter array",
+                32844 => "Pixar LogL",
+                32845 => "Pixar LogLuv",
+                34892 => "Linear raw",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn thresholding(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            Some(match v.first()? {
+                1 => "No dithering or halftoning",
+                2 => "Ordered dither or halftone",
+                3 => "Randomized dither",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn fill_order(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            Some(match v.first()? {
+                1 => "Most significant bit first",
+                2 => "Least significant bit first",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn planar_configuration(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            Some(match v.first()? {
+                1 => "Chunky",
+                2 => "Planar",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn gray_response_unit(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            Some(match v.first()? {
+                1 => "Tenths of a unit",
+                2 => "Hundredths of a unit",
+                3 => "Thousandths of a unit",
+                4 => "Ten-thousandths of a unit",
+                5 => "Hundred-thousandths of a unit",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+/// Lookup tables (color maps, response curves) are too long to be printed in full
+pub(crate) fn table_values(e: &TagValue) -> Option<Cow<'static, str>> {
+    let len = match *e {
+        TagValue::U16(ref v) => v.len(),
+        TagValue::U32(ref v) => v.len(),
+        TagValue::IRational(ref v) => v.len(),
+        TagValue::F32(ref v) => v.len(),
+        _ => return None,
+    };
+    Some(format!("Table of {} values", len).into())
+}
+
+pub(crate) fn page_number(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => match **v {
+            [page, 0, ..] => Some(format!("Page {}", u32::from(page) + 1).into()),
+            [page, total, ..] => Some(format!("Page {} of {}", u32::from(page) + 1, total).into()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn predictor(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            Some(match v.first()? {
+                1 => "None",
+                2 => "Horizontal differencing",
+                3 => "Floating point",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn ink_set(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            Some(match v.first()? {
+                1 => "CMYK",
+                2 => "Not CMYK",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn extra_samples(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            let names: Vec<Cow<'static, str>> = v.iter().map(|n| match n {
+                0 => "Unspecified".into(),
+                1 => "Associated alpha".into(),
+                2 => "Unassociated alpha".into(),
+                n => format!("Unknown ({})", n).into(),
+            }).collect();
+            Some(names.join(", ").into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn sample_format(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            let names: Vec<Cow<'static, str>> = v.iter().map(|n| match n {
+                1 => "Unsigned integer".into(),
+                2 => "Signed integer".into(),
+                3 => "IEEE floating point".into(),
+                4 => "Undefined".into(),
+                5 => "Complex integer".into(),
+                6 => "Complex IEEE floating point".into(),
+                n => format!("Unknown ({})", n).into(),
+            }).collect();
+            Some(names.join(", ").into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn jpeg_proc(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            Some(match v.first()? {
+                1 => "Baseline sequential",
+                14 => "Lossless Huffman",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn ycbcr_subsampling(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            let (horizontal, vertical) = match **v {
+                [horizontal, vertical, ..] => (horizontal, vertical),
+                _ => return None,
+            };
+            Some(match (horizontal, vertical) {
+                (1, 1) => "YCbCr 4:4:4",
+                (1, 2) => "YCbCr 4:4:0",
+                (2, 1) => "YCbCr 4:2:2",
+                (2, 2) => "YCbCr 4:2:0",
+                (4, 1) => "YCbCr 4:1:1",
+                (4, 2) => "YCbCr 4:1:0",
+                (h, v) => return Some(format!("Unknown ({}, {})", h, v).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn ycbcr_positioning(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            Some(match v.first()? {
+                1 => "Centered",
+                2 => "Co-sited",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+/// XMP is an XML document, far too long to be shown inline
+pub(crate) fn xmp_packet(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U8(ref v) | TagValue::Undefined(ref v, _) => Some(format!("XMP packet of {} bytes", v.len()).into()),
+        _ => None,
+    }
+}
+
+/// All values of a numeric tag as floating point, whatever the storage format
+fn f64_values(e: &TagValue) -> Vec<f64> {
+    (0..).map_while(|i| e.to_f64(i)).collect()
+}
+
+/// Numeric values that may be stored as integers or rationals, printed as decimals
+pub(crate) fn decimal_values(e: &TagValue) -> Option<Cow<'static, str>> {
+    let v = f64_values(e);
+    if v.is_empty() {
+        return None;
+    }
+    Some(NumArray::new(v).to_string().into())
+}
+
+/// Formats a row-major matrix as "[a, b, c] [d, e, f]"
+fn matrix(v: &[f64], columns: usize) -> String {
+    v.chunks(columns)
+        .map(|row| format!("[{}]", NumArray::new(row.iter().map(|x| format!("{:.4}", x)))))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Matrices with one row per color plane and 3 columns (XYZ), e.g. ColorMatrix
+pub(crate) fn matrix_3_columns(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::IRational(ref v) if v.len() % 3 == 0 => {
+            Some(matrix(&f64_values(e), 3).into())
+        },
+        TagValue::IRational(_) => table_values(e),
+        _ => None,
+    }
+}
+
+/// Matrices with 3 rows (XYZ) and one column per color plane, e.g. ForwardMatrix
+pub(crate) fn matrix_3_rows(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::IRational(ref v) if v.len() % 3 == 0 => {
+            Some(matrix(&f64_values(e), v.len() / 3).into())
+        },
+        TagValue::IRational(_) => table_values(e),
+        _ => None,
+    }
+}
+
+/// Square matrices with one row and one column per color plane, e.g. CameraCalibration
+pub(crate) fn matrix_square(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::IRational(ref v) => {
+            let side = (1..=v.len()).find(|n| n * n >= v.len())?;
+            if side * side != v.len() {
+                return table_values(e);
+            }
+            Some(matrix(&f64_values(e), side).into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn dng_version(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U8(ref v) => match **v {
+            [major, minor, revision, build, ..] => Some(format!("{}.{}.{}.{}", major, minor, revision, build).into()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Text that DNG allows to be stored either as ASCII or as a byte array
+pub(crate) fn byte_string(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::Ascii(ref s) => Some(s.clone().into()),
+        TagValue::U8(ref v) => {
+            let text = v.split(|&b| b == 0).next().unwrap_or_default();
+            Some(String::from_utf8_lossy(text).into_owned().into())
+        },
+        _ => None,
+    }
+}
+
+/// Prints an opaque byte array simply as "blob", noting its length
+pub(crate) fn byte_blob(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U8(ref v) => Some(format!("Blob of {} bytes", v.len()).into()),
+        _ => None,
+    }
+}
+
+/// MD5 digests and unique IDs, printed as hexadecimal
+pub(crate) fn hex_digest(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U8(ref v) => Some(v.iter().map(|b| format!("{:02X}", b)).collect::<String>().into()),
+        _ => None,
+    }
+}
+
+pub(crate) fn cfa_plane_color(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U8(ref v) => {
+            let names: Vec<Cow<'static, str>> = v.iter().map(|n| match n {
+                0 => "Red".into(),
+                1 => "Green".into(),
+                2 => "Blue".into(),
+                3 => "Cyan".into(),
+                4 => "Magenta".into(),
+                5 => "Yellow".into(),
+                6 => "White".into(),
+                n => format!("Unknown ({})", n).into(),
+            }).collect();
+            Some(names.join(", ").into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn cfa_layout(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            Some(match v.first()? {
+                1 => "Rectangular",
+                2 => "Staggered layout A",
+                3 => "Staggered layout B",
+                4 => "Staggered layout C",
+                5 => "Staggered layout D",
+                6 => "Staggered layout E",
+                7 => "Staggered layout F",
+                8 => "Staggered layout G",
+                9 => "Staggered layout H",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+/// Exposure compensation in EV stops, e.g. BaselineExposure
+pub(crate) fn ev_offset(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::IRational(ref v) => Some(format!("{:+.2} EV", v.first()?.value()).into()),
+        _ => None,
+    }
+}
+
+pub(crate) fn maker_note_safety(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            Some(match v.first()? {
+                0 => "Unsafe",
+                1 => "Safe",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+/// Rectangle given as top, left, bottom, right
+pub(crate) fn active_area(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *f64_values(e) {
+        [top, left, bottom, right, ..] => Some(format!(
+            "at {},{} width {} height {}",
+            left, top, right - left, bottom - top
+        ).into()),
+        _ => None,
+    }
+}
+
+pub(crate) fn colorimetric_reference(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            Some(match v.first()? {
+                0 => "Scene-referred",
+                1 => "Output-referred (ICC profile dynamic range)",
+                2 => "Output-referred (high dynamic range)",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn profile_embed_policy(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U32(ref v) => {
+            Some(match v.first()? {
+                0 => "Allow copying",
+                1 => "Embed if used",
+                2 => "Never embed",
+                3 => "No restrictions",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn preview_color_space(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U32(ref v) => {
+            Some(match v.first()? {
+                0 => "Unknown",
+                1 => "Gray gamma 2.2",
+                2 => "sRGB",
+                3 => "Adobe RGB",
+                4 => "ProPhoto RGB",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn profile_encoding(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U32(ref v) => {
+            Some(match v.first()? {
+                0 => "Linear",
+                1 => "sRGB",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn default_black_render(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U32(ref v) => {
+            Some(match v.first()? {
+                0 => "Auto",
+                1 => "None",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn t_stop(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::IRational(ref v) => Some(match **v {
+            [t] => format!("T{:.1}", t.value()),
+            [min, max] => format!("T{:.1}-T{:.1}", min.value(), max.value()),
+            _ => return None,
+        }.into()),
+        _ => None,
+    }
+}
+
+pub(crate) fn depth_format(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            Some(match v.first()? {
+                0 => "Unknown",
+                1 => "Linear",
+                2 => "Inverse",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn depth_units(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            Some(match v.first()? {
+                0 => "Unknown",
+                1 => "m",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn depth_measure_type(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::U16(ref v) => {
+            Some(match v.first()? {
+                0 => "Unknown",
+                1 => "Optical axis",
+                2 => "Optical ray",
+                n => return Some(format!("Unknown ({})", n).into()),
+            }.into())
+        },
+        _ => None,
+    }
+}
+
+/// Lists the opcodes of a DNG opcode list. Opcode lists are always big-endian,
+/// regardless of the byte order of the TIFF container.
+pub(crate) fn opcode_list(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::Undefined(ref v, _) => {
+            let count = read_u32(false, v)?;
+            let mut names = vec![];
+            let mut rest = v.get(4..)?;
+            for _ in 0..count {
+                let id = read_u32(false, rest)?;
+                let size = read_u32(false, rest.get(12..)?)? as usize;
+                names.push(match id {
+                    1 => "WarpRectilinear".into(),
+                    2 => "WarpFisheye".into(),
+                    3 => "FixVignetteRadial".into(),
+                    4 => "FixBadPixelsConstant".into(),
+                    5 => "FixBadPixelsList".into(),
+                    6 => "TrimBounds".into(),
+                    7 => "MapTable".into(),
+                    8 => "MapPolynomial".into(),
+                    9 => "GainMap".into(),
+                    10 => "DeltaPerRow".into(),
+                    11 => "DeltaPerColumn".into(),
+                    12 => "ScalePerRow".into(),
+                    13 => "ScalePerColumn".into(),
+                    14 => "WarpRectilinear2".into(),
+                    n => format!("Unknown ({})", n),
+                });
+                rest = rest.get(16 + size..)?;
+            }
+            if names.is_empty() {
+                return Some("No opcodes".into());
+            }
+            Some(names.join(", ").into())
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn interop_index(e: &TagValue) -> Option<Cow<'static, str>> {
+    match *e {
+        TagValue::Ascii(ref v) => {
+            Some(match v.as_str() {
+                "R98" => "R98 (DCF basic file, sRGB)".into(),
+                "R03" => "R03 (DCF option file, Adobe RGB)".into(),
+                "THM" => "THM (DCF thumbnail file)".into(),
+                v => format!("Unknown ({})", v).into(),
+            })
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,80 @@
+use super::types::*;
+
+/// Exposure settings of the picture, combined from the direct and the APEX tags
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Exposure {
+    /// Shutter speed in seconds, from ExposureTime or ShutterSpeedValue
+    pub exposure_time: Option<f64>,
+    /// From FNumber or ApertureValue
+    pub f_number: Option<f64>,
+    /// Sensitivity, from the tag named by SensitivityType, or ISOSpeedRatings
+    pub iso: Option<u32>,
+    /// Exposure compensation in EV, from ExposureBiasValue
+    pub exposure_bias: Option<f64>,
+    /// Exposure value of the settings, normalized to ISO 100:
+    /// log2(f-number² / exposure time) - log2(ISO / 100)
+    pub ev100: Option<f64>,
+    /// Scene light value at ISO 100. It comes from BrightnessValue when the camera
+    /// recorded it, otherwise it is `ev100` corrected by the exposure bias.
+    pub light_value: Option<f64>,
+}
+
+impl ExifData {
+    /// Exposure settings, or `None` if the image has none of the exposure tags.
+    ///
+    /// ExposureTime and FNumber are preferred over the APEX ShutterSpeedValue and
+    /// ApertureValue, because APEX values are often rounded, and the two sometimes
+    /// disagree. The APEX values are used when the direct tags are missing.
+    pub fn exposure(&self) -> Option<Exposure> {
+        let positive = |tag| self.get(tag)?.value.to_f64(0).filter(|v| v.is_finite() && *v > 0.0);
+
+        let exposure_time = positive(ExifTag::ExposureTime)
+            .or_else(|| self.apex(ExifTag::ShutterSpeedValue).map(|tv| 2f64.powf(-tv)));
+        let f_number = positive(ExifTag::FNumber)
+            .or_else(|| self.apex(ExifTag::ApertureValue).map(|av| 2f64.powf(av / 2.0)));
+        let iso = self.iso();
+        let exposure_bias = self.apex(ExifTag::ExposureBiasValue);
+
+        let ev100 = match (exposure_time, f_number, iso) {
+            (Some(t), Some(n), Some(iso)) if iso > 0 => Some((n * n / t).log2() - (f64::from(iso) / 100.0).log2()),
+            _ => None,
+        };
+        // APEX Bv = Av + Tv - Sv, where Sv is 5 at ISO 100
+        let light_value = self.apex(ExifTag::BrightnessValue).map(|bv| bv + 5.0)
+            .or_else(|| Some(ev100? + exposure_bias.unwrap_or(0.0)));
+
+        let exposure = Exposure { exposure_time, f_number, iso, exposure_bias, ev100, light_value };
+        if exposure == Exposure::default() {
+            return None;
+        }
+        Some(exposure)
+    }
+
+    /// ISO sensitivity. SensitivityType tells which of the Exif 2.3 tags the
+    /// camera considers the sensitivity; older files only have ISOSpeedRatings,
+    /// which saturates at 65535.
+    fn iso(&self) -> Option<u32> {
+        let value = |tag| self.get_u32(tag).filter(|&n| n > 0);
+        let preferred = match self.get_u32(ExifTag::SensitivityType) {
+            Some(1) | Some(4) | Some(5) | Some(7) => value(ExifTag::StandardOutputSensitivity),
+            Some(2) | Some(6) => value(ExifTag::RecommendedExposureIndex),
+            Some(3) => value(ExifTag::ISOSpeed),
+            _ => None,
+        };
+        preferred
+            .or_else(|| value(ExifTag::ISOSpeedRatings).filter(|&n| n < 65535))
+            .or_else(|| value(ExifTag::ISOSpeed))
+            .or_else(|| value(ExifTag::StandardOutputSensitivity))
+            .or_else(|| value(ExifTag::RecommendedExposureIndex))
+            .or_else(|| value(ExifTag::ISOSpeedRatings))
+    }
+
+    /// Value of an APEX tag, or `None` if it is missing or unknown
+    fn apex(&self, tag: ExifTag) -> Option<f64> {
+        match self.get(tag)?.value {
+            // A numerator of 0xFFFFFFFF means unknown
+            TagValue::IRational(ref v) if v.first()?.numerator == -1 => None,
+            ref v => v.to_f64(0).filter(|v| v.is_finite()),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use super::types::*;
+use std::convert::TryFrom;
+
+/// Status of returned light, bits 1 and 2 of the Flash tag
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FlashReturn {
+    /// No strobe return detection function
+    NoDetection = 0,
+    Reserved = 1,
+    /// Strobe return light not detected
+    NotDetected = 2,
+    /// Strobe return light detected
+    Detected = 3,
+}
+
+/// Flash mode, bits 3 and 4 of the Flash tag
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FlashMode {
+    Unknown = 0,
+    /// Compulsory flash firing
+    On = 1,
+    /// Compulsory flash suppression
+    Off = 2,
+    Auto = 3,
+}
+
+/// Decoded bit field of the Flash tag
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Flash {
+    /// Bit 0
+    pub fired: bool,
+    /// Bits 1 and 2
+    pub return_light: FlashReturn,
+    /// Bits 3 and 4
+    pub mode: FlashMode,
+    /// Bit 5, inverted: the tag sets the bit when there is *no* flash function
+    pub function_present: bool,
+    /// Bit 6
+    pub red_eye_reduction: bool,
+}
+
+impl Flash {
+    /// Decode the tag value. Reserved bits 7 to 15 are ignored.
+    pub fn from_u16(n: u16) -> Self {
+        Flash {
+            fired: n & 1 != 0,
+            return_light: match (n >> 1) & 3 {
+                0 => FlashReturn::NoDetection,
+                1 => FlashReturn::Reserved,
+                2 => FlashReturn::NotDetected,
+                _ => FlashReturn::Detected,
+            },
+            mode: match (n >> 3) & 3 {
+                0 => FlashMode::Unknown,
+                1 => FlashMode::On,
+                2 => FlashMode::Off,
+                _ => FlashMode::Auto,
+            },
+            function_present: n & (1 << 5) == 0,
+            red_eye_reduction: n & (1 << 6) != 0,
+        }
+    }
+
+    /// Encode as a tag value
+    pub fn to_u16(self) -> u16 {
+        u16::from(self.fired)
+            | (self.return_light as u16) << 1
+            | (self.mode as u16) << 3
+            | u16::from(!self.function_present) << 5
+            | u16::from(self.red_eye_reduction) << 6
+    }
+}
+
+impl From<u16> for Flash {
+    fn from(n: u16) -> Flash {
+        Flash::from_u16(n)
+    }
+}
+
+impl From<Flash> for u16 {
+    fn from(f: Flash) -> u16 {
+        f.to_u16()
+    }
+}
+
+impl ExifData {
+    /// Decoded Flash tag, if present and small enough for a SHORT
+    pub fn flash(&self) -> Option<Flash> {
+        let n = self.get_u32(ExifTag::Flash)?;
+        u16::try_from(n).ok().map(Flash::from_u16)
+    }
+}
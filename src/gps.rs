@@ -0,0 +1,153 @@
+use super::datetime::system_time;
+use super::rational::*;
+use super::types::*;
+use std::time::SystemTime;
+
+/// Direction of the GPS IFD (track, image direction), in degrees from 0 to 359.99
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GpsBearing {
+    pub degrees: f64,
+    /// `true` if relative to magnetic north, `false` if relative to true north
+    pub magnetic: bool,
+}
+
+/// Position and related data from the GPS IFD, converted to decimal and SI units
+///
+/// Every field is optional, because every GPS tag is. A field is also `None` when
+/// its tag is malformed, e.g. it has a zero denominator, or its reference tag is
+/// needed and missing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GpsInfo {
+    /// Decimal degrees, positive north of the equator
+    pub latitude: Option<f64>,
+    /// Decimal degrees, positive east of Greenwich
+    pub longitude: Option<f64>,
+    /// Meters, negative below sea level
+    pub altitude: Option<f64>,
+    /// UTC time of the fix, from GPSDateStamp and GPSTimeStamp
+    pub timestamp: Option<SystemTime>,
+    /// Speed of the receiver, in meters per second
+    pub speed: Option<f64>,
+    /// Direction of movement of the receiver
+    pub track: Option<GpsBearing>,
+    /// Direction the image was taken towards
+    pub image_direction: Option<GpsBearing>,
+    /// Dilution of precision (HDOP in 2D measure mode, PDOP in 3D)
+    pub dop: Option<f64>,
+    /// Geodetic datum, e.g. "WGS-84"
+    pub map_datum: Option<String>,
+}
+
+impl ExifData {
+    /// Position, altitude, time and motion recorded in the GPS IFD.
+    ///
+    /// Returns `None` if the image has no GPS tags at all.
+    pub fn gps(&self) -> Option<GpsInfo> {
+        if !self.entries.iter().any(|e| e.kind == IfdKind::Gps) {
+            return None;
+        }
+
+        let value = |tag| self.get_in(IfdKind::Gps, tag).map(|e| &e.value);
+        let rationals = |tag| match value(tag) {
+            Some(TagValue::URational(v)) => Some(&v[..]),
+            _ => None,
+        };
+        let rational = |tag| rationals(tag).and_then(|v| finite(v.first()?));
+        let text = |tag| value(tag)?.as_str().map(str::trim);
+
+        let latitude = match text(ExifTag::GPSLatitudeRef) {
+            Some("N") => Some(1.0),
+            Some("S") => Some(-1.0),
+            _ => None,
+        }.and_then(|sign| Some(sign * degrees(rationals(ExifTag::GPSLatitude)?)?))
+            .filter(|d| d.abs() <= 90.0);
+
+        let longitude = match text(ExifTag::GPSLongitudeRef) {
+            Some("E") => Some(1.0),
+            Some("W") => Some(-1.0),
+            _ => None,
+        }.and_then(|sign| Some(sign * degrees(rationals(ExifTag::GPSLongitude)?)?))
+            .filter(|d| d.abs() <= 180.0);
+
+        // A missing GPSAltitudeRef means above sea level
+        let below_sea_level = match value(ExifTag::GPSAltitudeRef) {
+            Some(TagValue::U8(v)) => v.first() == Some(&1),
+            _ => false,
+        };
+        let altitude = rational(ExifTag::GPSAltitude)
+            .map(|a| if below_sea_level { -a } else { a });
+
+        let timestamp = text(ExifTag::GPSDateStamp)
+            .and_then(|date| utc_timestamp(date, rationals(ExifTag::GPSTimeStamp)?));
+
+        // A missing GPSSpeedRef means km/h
+        let speed = rational(ExifTag::GPSSpeed).and_then(|s| match text(ExifTag::GPSSpeedRef) {
+            None | Some("K") => Some(s / 3.6),
+            Some("M") => Some(s * 0.447_04),
+            Some("N") => Some(s * 1852.0 / 3600.0),
+            _ => None,
+        });
+
+        let bearing = |tag, ref_tag| {
+            let degrees = rational(tag)?;
+            // A missing reference means true north
+            let magnetic = match text(ref_tag) {
+                None | Some("T") => false,
+                Some("M") => true,
+                _ => return None,
+            };
+            Some(GpsBearing { degrees, magnetic })
+        };
+
+        Some(GpsInfo {
+            latitude,
+            longitude,
+            altitude,
+            timestamp,
+            speed,
+            track: bearing(ExifTag::GPSTrack, ExifTag::GPSTrackRef),
+            image_direction: bearing(ExifTag::GPSImgDirection, ExifTag::GPSImgDirectionRef),
+            dop: rational(ExifTag::GPSDOP),
+            map_datum: text(ExifTag::GPSMapDatum).filter(|d| !d.is_empty()).map(String::from),
+        })
+    }
+}
+
+/// Value of a rational, or `None` if the denominator is zero
+fn finite(r: &URational) -> Option<f64> {
+    if r.denominator == 0 {
+        None
+    } else {
+        Some(r.value())
+    }
+}
+
+/// Converts degrees, minutes and seconds to decimal degrees. Minutes and seconds
+/// are optional, and some writers encode an unused field as 0/0.
+fn degrees(dms: &[URational]) -> Option<f64> {
+    let part = |i: usize| match dms.get(i) {
+        None => Some(0.0),
+        Some(r) if r.numerator == 0 => Some(0.0),
+        Some(r) => finite(r),
+    };
+    Some(finite(dms.first()?)? + part(1)? / 60.0 + part(2)? / 3600.0)
+}
+
+/// Combines "YYYY:MM:DD" and an hour, minute, second triplet into a point in time
+fn utc_timestamp(date: &str, time: &[URational]) -> Option<SystemTime> {
+    let mut parts = date.split([':', '-']);
+    let year: u16 = parts.next()?.trim().parse().ok()?;
+    let month = parts.next()?.trim().parse().ok()?;
+    let day = parts.next()?.trim().parse().ok()?;
+
+    let hour = finite(time.first()?)?;
+    let minute = finite(time.get(1)?)?;
+    let second = finite(time.get(2)?)?;
+    if !(0.0..24.0).contains(&hour) || !(0.0..60.0).contains(&minute) || !(0.0..61.0).contains(&second) {
+        return None;
+    }
+
+    let seconds = hour.trunc() * 3600.0 + minute.trunc() * 60.0 + second;
+    let nanos = (seconds.fract() * 1e9).round() as u32;
+    system_time(year, month, day, seconds.trunc() as i64, nanos)
+}
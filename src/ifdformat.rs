@@ -20,9 +20,9 @@ impl<T: Display, I: IntoIterator<Item=T>> Display for NumArray<I> {
         for number in iter {
             if !first {
                 write!(f, ", {}", number)?;
-                first = false;
             } else {
                 write!(f, "{}", number)?;
+                first = false;
             }
         }
         Ok(())
@@ -32,7 +32,7 @@ impl<T: Display, I: IntoIterator<Item=T>> Display for NumArray<I> {
 /// Convert a IfdEntry into a tuple of TagValue
 pub(crate) fn tag_value_new(f: &IfdEntry) -> Option<TagValue> {
     Some(match f.format {
-        IfdFormat::Ascii => {
+        IfdFormat::Ascii | IfdFormat::Utf8 => {
             // Remove \0, there may be more than one
             let mut data = &f.data[..];
             while let Some((&val, rest)) = data.split_last() {
@@ -73,11 +73,11 @@ pub(crate) fn tag_value_new(f: &IfdEntry) -> Option<TagValue> {
             TagValue::I32(a)
         }
         IfdFormat::F32 => {
-            let a = read_f32_array(f.count, &f.data)?;
+            let a = read_f32_array(f.le, f.count, &f.data)?;
             TagValue::F32(a)
         }
         IfdFormat::F64 => {
-            let a = read_f64_array(f.count, &f.data)?;
+            let a = read_f64_array(f.le, f.count, &f.data)?;
             TagValue::F64(a)
         }
         IfdFormat::URational => {
@@ -97,12 +97,88 @@ pub(crate) fn tag_value_new(f: &IfdEntry) -> Option<TagValue> {
     })
 }
 
+/// Convert a TagValue into the format, count and raw data of an IFD entry. The
+/// inverse of `tag_value_new`. Strings get their terminating \0.
+pub(crate) fn tag_value_encode(value: &TagValue, le: bool) -> (IfdFormat, u32, Vec<u8>) {
+    let mut data = Vec::new();
+    macro_rules! extend {
+        ($v:expr) => {
+            for n in $v {
+                data.extend(&if le { n.to_le_bytes() } else { n.to_be_bytes() });
+            }
+        };
+    }
+
+    let (format, count) = match *value {
+        TagValue::U8(ref v) => {
+            data.extend(v);
+            (IfdFormat::U8, v.len())
+        },
+        TagValue::Ascii(ref s) => {
+            data.extend(s.as_bytes());
+            data.push(0);
+            // Exif 3.0 type for text that is not ASCII
+            (if s.is_ascii() { IfdFormat::Ascii } else { IfdFormat::Utf8 }, data.len())
+        },
+        TagValue::U16(ref v) => {
+            extend!(v);
+            (IfdFormat::U16, v.len())
+        },
+        TagValue::U32(ref v) => {
+            extend!(v);
+            (IfdFormat::U32, v.len())
+        },
+        TagValue::URational(ref v) => {
+            for r in v {
+                extend!(&[r.numerator, r.denominator]);
+            }
+            (IfdFormat::URational, v.len())
+        },
+        TagValue::I8(ref v) => {
+            data.extend(v.iter().map(|&n| n as u8));
+            (IfdFormat::I8, v.len())
+        },
+        TagValue::Undefined(ref v, _) => {
+            data.extend(v);
+            (IfdFormat::Undefined, v.len())
+        },
+        TagValue::I16(ref v) => {
+            extend!(v);
+            (IfdFormat::I16, v.len())
+        },
+        TagValue::I32(ref v) => {
+            extend!(v);
+            (IfdFormat::I32, v.len())
+        },
+        TagValue::IRational(ref v) => {
+            for r in v {
+                extend!(&[r.numerator, r.denominator]);
+            }
+            (IfdFormat::IRational, v.len())
+        },
+        TagValue::F32(ref v) => {
+            extend!(v);
+            (IfdFormat::F32, v.len())
+        },
+        TagValue::F64(ref v) => {
+            extend!(v);
+            (IfdFormat::F64, v.len())
+        },
+        TagValue::Unknown(ref v, _) => {
+            data.extend(v);
+            (IfdFormat::Unknown, v.len())
+        },
+        TagValue::Invalid(ref v, _, format, count) => return (IfdFormat::new(format), count, v.clone()),
+    };
+    (format, count as u32, data)
+}
+
 /// Compare two vectors of floats, and always consider NaN == NaN.
 fn vec_cmp<F: Float>(va: &[F], vb: &[F]) -> bool {
     (va.len() == vb.len()) &&  // zip stops at the shortest
      va.iter()
        .zip(vb)
-       .all(|(a,b)| (a.is_nan() && b.is_nan() || (a == b)))
+       .all(|(a,b)| a.is_nan() && b.is_nan() || (a == b))
 }
 
 /// Check if `left` == `right`. If the `left` and `right` are float vectors, this returns `true` even
@@ -110,8 +186,8 @@ fn vec_cmp<F: Float>(va: &[F], vb: &[F]) -> bool {
 /// values at the same positions).
 pub(crate) fn tag_value_eq(left: &TagValue, right: &TagValue) -> bool {
     match (left, right) {
-        (TagValue::F32(x), TagValue::F32(y)) => vec_cmp(&x, &y),
-        (TagValue::F64(x), TagValue::F64(y)) => vec_cmp(&x, &y),
+        (TagValue::F32(x), TagValue::F32(y)) => vec_cmp(x, y),
+        (TagValue::F64(x), TagValue::F64(y)) => vec_cmp(x, y),
         (x, y) => x == y,
     }
 }
@@ -31,41 +31,29 @@ pub(crate) fn detect_type(contents: &[u8]) -> FileType {
         return FileType::Unknown;
     }
 
-    if contents[0] == 0xff && contents[1] == 0xd8 && contents[2] == 0xff && // contents[3] == 0xe0 &&
-        contents[6] == b'J' && contents[7] == b'F' && contents[8] == b'I' && contents[9] == b'F' &&
-            contents[10] == 0
-    {
-        return FileType::JPEG;
-    }
-    if contents[0] == 0xff && contents[1] == 0xd8 && contents[2] == 0xff && // contents[3] == 0xe0
-        contents[6] == b'E' && contents[7] == b'x' && contents[8] == b'i' && contents[9] == b'f' &&
-            contents[10] == 0
-    {
-        return FileType::JPEG;
-    }
-    if contents[0] == b'I' && contents[1] == b'I' && contents[2] == 42 && contents[3] == 0 {
+    match *contents {
+        // contents[3] == 0xe0
+        [0xff, 0xd8, 0xff, _, _, _, b'J', b'F', b'I', b'F', 0, ..] => FileType::JPEG,
+        [0xff, 0xd8, 0xff, _, _, _, b'E', b'x', b'i', b'f', 0, ..] => FileType::JPEG,
         /* TIFF little-endian */
-        return FileType::TIFF;
-    }
-    if contents[0] == b'M' && contents[1] == b'M' && contents[2] == 0 && contents[3] == 42 {
+        [b'I', b'I', 42, 0, ..] => FileType::TIFF,
         /* TIFF big-endian */
-        return FileType::TIFF;
+        [b'M', b'M', 0, 42, ..] => FileType::TIFF,
+        _ => FileType::Unknown,
     }
-    FileType::Unknown
 }
 
 /// Find the embedded TIFF in a JPEG image (that in turn contains the EXIF data)
 pub fn find_embedded_tiff_in_jpeg(contents: &[u8]) -> Result<(usize, usize), ExifError> {
-    let mut offset = 2 as usize;
+    let mut offset = 2_usize;
 
     while offset < contents.len() {
-        if contents.len() < (offset + 4) {
-            return Err(ExifError::JpegWithoutExif(
+        let (marker, size) = match contents.get(offset..offset + 4) {
+            Some(&[m0, m1, s0, s1]) => (u16::from_be_bytes([m0, m1]), usize::from(u16::from_be_bytes([s0, s1]))),
+            _ => return Err(ExifError::JpegWithoutExif(
                 "JPEG truncated in marker header".to_string(),
-            ));
-        }
-
-        let marker: u16 = u16::from(contents[offset]) * 256 + u16::from(contents[offset + 1]);
+            )),
+        };
 
         if marker < 0xff00 {
             return Err(ExifError::JpegWithoutExif(format!(
@@ -75,7 +63,6 @@ pub fn find_embedded_tiff_in_jpeg(contents: &[u8]) -> Result<(usize, usize), Exi
         }
 
         offset += 2;
-        let size = (contents[offset] as usize) * 256 + (contents[offset + 1] as usize);
 
         if size < 2 {
             return Err(ExifError::JpegWithoutExif(
@@ -95,7 +82,7 @@ pub fn find_embedded_tiff_in_jpeg(contents: &[u8]) -> Result<(usize, usize), Exi
                 ));
             }
 
-            if contents[offset + 2..offset + 8] != [b'E', b'x', b'i', b'f', 0, 0] {
+            if contents.get(offset + 2..offset + 8) != Some(b"Exif\0\0".as_ref()) {
                 return Err(ExifError::JpegWithoutExif(
                     "EXIF preamble unrecognized".to_string(),
                 ));
@@ -0,0 +1,72 @@
+use super::rational::*;
+use super::types::*;
+
+/// Lens description merged from LensMake, LensModel, LensSerialNumber,
+/// LensSpecification (or the DNG LensInfo tag), FocalLength and MaxApertureValue.
+///
+/// Vendor lens IDs are not included, because MakerNotes are not parsed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LensInfo {
+    pub make: Option<String>,
+    pub model: Option<String>,
+    pub serial_number: Option<String>,
+    /// Shortest focal length, in mm
+    pub min_focal_length: Option<f64>,
+    /// Longest focal length, in mm. Equal to `min_focal_length` for prime lenses.
+    pub max_focal_length: Option<f64>,
+    /// Smallest f-number (widest aperture) at the shortest focal length
+    pub min_f_number: Option<f64>,
+    /// Smallest f-number (widest aperture) at the longest focal length. Equal to
+    /// `min_f_number` for lenses with a constant aperture.
+    pub max_f_number: Option<f64>,
+}
+
+impl ExifData {
+    /// Lens used to take the picture, or `None` if none of the tags above is present.
+    ///
+    /// When neither LensSpecification nor LensInfo is present, the focal length
+    /// range and f-numbers fall back to FocalLength and MaxApertureValue. Those are
+    /// the values of this one picture, not of the whole range of a zoom lens.
+    pub fn lens(&self) -> Option<LensInfo> {
+        let text = |tag| self.get_str(tag).map(str::trim).filter(|s| !s.is_empty()).map(String::from);
+
+        // 0/0 marks an unknown value
+        let spec = self.get(ExifTag::LensSpecification)
+            .or_else(|| self.get(ExifTag::LensInfo))
+            .and_then(|e| match e.value {
+                TagValue::URational(ref v) => match v.as_slice() {
+                    [a, b, c, d, ..] => Some([known(a), known(b), known(c), known(d)]),
+                    _ => None,
+                },
+                _ => None,
+            });
+
+        let [min_focal, max_focal, min_f, max_f] = spec.unwrap_or_else(|| {
+            let focal = self.get_rational(ExifTag::FocalLength).as_ref().and_then(known);
+            // APEX aperture value Av = 2 log2(f-number)
+            let f_number = self.get_rational(ExifTag::MaxApertureValue)
+                .filter(|av| av.denominator != 0)
+                .map(|av| 2f64.powf(av.value() / 2.0));
+            [focal, focal, f_number, f_number]
+        });
+
+        let lens = LensInfo {
+            make: text(ExifTag::LensMake),
+            model: text(ExifTag::LensModel),
+            serial_number: text(ExifTag::LensSerialNumber),
+            min_focal_length: min_focal,
+            max_focal_length: max_focal.or(min_focal),
+            min_f_number: min_f,
+            max_f_number: max_f.or(min_f),
+        };
+        if lens == LensInfo::default() {
+            return None;
+        }
+        Some(lens)
+    }
+}
+
+/// Value of a rational, or `None` for 0/0 and other zero or invalid values
+fn known(r: &URational) -> Option<f64> {
+    Some(r.value()).filter(|v| v.is_finite() && *v > 0.0)
+}
@@ -1,4 +1,5 @@
 #![allow(clippy::upper_case_acronyms)]
+#![deny(clippy::indexing_slicing)]
 
 //! RExif is a native Rust create, written to extract EXIF data from JPEG and TIFF images.
 //!
@@ -41,10 +42,13 @@ use std::path::Path;
 mod lowlevel;
 mod rational;
 pub use self::rational::*;
+mod access;
 mod types;
 pub use self::types::*;
 mod types_impl;
 pub use self::types_impl::*;
+mod options;
+pub use self::options::*;
 mod image;
 use self::image::*;
 mod ifdformat;
@@ -53,14 +57,40 @@ use self::tiff::*;
 mod exif;
 mod exifpost;
 mod exifreadable;
+mod text;
+pub use self::text::*;
+mod datetime;
+pub use self::datetime::*;
+mod gps;
+pub use self::gps::*;
+mod orientation;
+pub use self::orientation::*;
+mod flash;
+pub use self::flash::*;
+mod lens;
+pub use self::lens::*;
+mod exposure;
+pub use self::exposure::*;
+mod builder;
+pub use self::builder::*;
+mod edit;
+mod redact;
+pub use self::redact::*;
+mod patch;
+pub use self::patch::*;
+mod write;
+pub use self::write::*;
 
 /// Parse a byte buffer that should contain a TIFF or JPEG image.
 /// Tries to detect format and parse EXIF data.
 ///
-/// Prints warnings to stderr.
+/// Prints warnings to stderr. Unknown tags and entries whose data is beyond the
+/// end of the file are not printed; `parse_buffer_quiet()` returns those too.
 pub fn parse_buffer(contents: &[u8]) -> ExifResult {
     let (res, warnings) = parse_buffer_quiet(contents);
-    warnings.into_iter().for_each(|w| eprintln!("{}", w));
+    warnings.into_iter()
+        .filter(|w| !matches!(w, ParseWarning::UnknownTag { .. } | ParseWarning::DataBeyondEof { .. }))
+        .for_each(|w| eprintln!("{}", w));
     res
 }
 
@@ -68,30 +98,54 @@ pub fn parse_buffer(contents: &[u8]) -> ExifResult {
 /// Tries to detect format and parse EXIF data.
 ///
 /// Returns warnings alongside result.
-pub fn parse_buffer_quiet(contents: &[u8]) -> (ExifResult, Vec<String>) {
+pub fn parse_buffer_quiet(contents: &[u8]) -> (ExifResult, Vec<ParseWarning>) {
+    parse_buffer_with_options(contents, &ParseOptions::default())
+}
+
+/// Parse a byte buffer that should contain a TIFF or JPEG image, with the
+/// given options. Returns warnings alongside result.
+///
+/// In strict mode, the warnings are returned even if they caused an error.
+pub fn parse_buffer_with_options(contents: &[u8], options: &ParseOptions) -> (ExifResult, Vec<ParseWarning>) {
     let mime = detect_type(contents);
     let mut warnings = vec![];
-    let (entries, le) = match mime {
+    let (tiff_offset, tiff_size) = match mime {
         FileType::Unknown => return (Err(ExifError::FileTypeUnknown), warnings),
-        FileType::TIFF => parse_tiff(contents, &mut warnings),
-        FileType::JPEG => {
-            match find_embedded_tiff_in_jpeg(contents).and_then(|(offset, size)| {
-                Ok(parse_tiff(&contents[offset..offset + size], &mut warnings))
-            }) {
-                Ok(r) => r,
-                Err(e) => return (Err(e), warnings)
-            }
-        }
+        FileType::TIFF => (0, contents.len()),
+        FileType::JPEG => match find_embedded_tiff_in_jpeg(contents) {
+            Ok(r) => r,
+            Err(e) => return (Err(e), warnings)
+        },
     };
+    let tiff = match contents.get(tiff_offset..tiff_offset + tiff_size) {
+        Some(tiff) => tiff,
+        None => return (Err(ExifError::TiffTruncated), warnings),
+    };
+    let mut positions = EntryPositions::default();
+    let (entries, le) = parse_tiff(tiff, options, &mut warnings, &mut positions);
+
+    let entries = entries.and_then(|entries| {
+        match warnings.iter().find(|w| w.is_non_conforming()) {
+            Some(w) if options.mode == ParseMode::Strict => Err(ExifError::NonConforming(w.clone())),
+            _ => Ok(entries),
+        }
+    });
 
-    (
-        entries.map(|entries| ExifData {
-            mime: mime.as_str(),
-            entries,
-            le
-        }),
-        warnings,
-    )
+    // Report positions in the file, rather than in the TIFF data
+    warnings.iter_mut().for_each(|w| w.relocate(tiff_offset));
+    let res = match entries {
+        Ok(entries) => {
+            let mut exif = ExifData::new(mime.as_str(), entries, le);
+            exif.tiff_offset = tiff_offset;
+            exif.positions = positions;
+            Ok(exif)
+        },
+        Err(mut e) => {
+            e.relocate(tiff_offset);
+            Err(e)
+        },
+    };
+    (res, warnings)
 }
 
 /// Try to read and parse an open file that is expected to contain an image
@@ -12,7 +12,7 @@ fn main() {
         process::exit(2);
     }
     for arg in &args[1..] {
-        match rexif::parse_file(&arg) {
+        match rexif::parse_file(arg) {
             Ok(exif) => {
                 for entry in &exif.entries {
                     if entry.tag == ExifTag::UnknownToMe {
@@ -0,0 +1,55 @@
+/// How strictly the parser holds images to the standard
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ParseMode {
+    /// Keep every entry that can be recovered, and report the problems as warnings.
+    /// Only an unreadable file header or IFD0 is an error.
+    Lenient,
+    /// Parse the whole image as in lenient mode, then fail with
+    /// `ExifError::NonConforming` carrying the first of its warnings. Unknown
+    /// tags are still accepted, and do not count as warnings here.
+    Strict,
+}
+
+/// Options of `parse_buffer_with_options()`
+///
+/// The limits bound the work and memory one file can cost, whatever it declares.
+/// Going over any of them is an error in both modes. The defaults are far above
+/// what cameras write.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub mode: ParseMode,
+    /// Number of IFD entries read, over all IFDs. Default 10 000.
+    pub max_entries: usize,
+    /// Size in bytes of the data of one entry. Default 16 MiB.
+    pub max_value_bytes: usize,
+    /// Size in bytes of the data of all entries together. Default 64 MiB.
+    pub max_total_bytes: usize,
+    /// Nesting of IFDs, IFD0 being at depth 0 and the Exif IFD at depth 1. Default 4.
+    pub max_ifd_depth: usize,
+    /// Number of IFDs read. Default 32.
+    pub max_ifds: usize,
+}
+
+impl ParseOptions {
+    /// Options of `parse_buffer()`
+    pub fn lenient() -> Self {
+        ParseOptions {
+            mode: ParseMode::Lenient,
+            max_entries: 10_000,
+            max_value_bytes: 16 << 20,
+            max_total_bytes: 64 << 20,
+            max_ifd_depth: 4,
+            max_ifds: 32,
+        }
+    }
+
+    pub fn strict() -> Self {
+        ParseOptions { mode: ParseMode::Strict, ..ParseOptions::lenient() }
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions::lenient()
+    }
+}
@@ -0,0 +1,152 @@
+use super::types::*;
+use std::convert::TryFrom;
+
+/// Value of the Orientation tag, named after the transform that turns the stored
+/// pixels into the image as it should be displayed.
+///
+/// Every orientation is an optional horizontal mirror, applied first, followed by a
+/// clockwise rotation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    /// 1: no transform needed
+    Normal = 1,
+    /// 2: mirror left to right
+    MirrorHorizontal = 2,
+    /// 3: rotate by 180°
+    Rotate180 = 3,
+    /// 4: mirror top to bottom
+    MirrorVertical = 4,
+    /// 5: mirror along the top-left to bottom-right diagonal
+    Transpose = 5,
+    /// 6: rotate 90° clockwise
+    Rotate90 = 6,
+    /// 7: mirror along the top-right to bottom-left diagonal
+    Transverse = 7,
+    /// 8: rotate 270° clockwise
+    Rotate270 = 8,
+}
+
+impl Orientation {
+    /// Orientation of a tag value, or `None` if it is not between 1 and 8
+    pub fn from_u16(value: u16) -> Option<Self> {
+        Some(match value {
+            1 => Orientation::Normal,
+            2 => Orientation::MirrorHorizontal,
+            3 => Orientation::Rotate180,
+            4 => Orientation::MirrorVertical,
+            5 => Orientation::Transpose,
+            6 => Orientation::Rotate90,
+            7 => Orientation::Transverse,
+            8 => Orientation::Rotate270,
+            _ => return None,
+        })
+    }
+
+    /// Orientation made of an optional horizontal mirror followed by a clockwise
+    /// rotation, or `None` if the rotation is not a multiple of 90°
+    pub fn from_parts(mirrored: bool, rotation: u32) -> Option<Self> {
+        Some(match (mirrored, rotation % 360) {
+            (false, 0) => Orientation::Normal,
+            (false, 90) => Orientation::Rotate90,
+            (false, 180) => Orientation::Rotate180,
+            (false, 270) => Orientation::Rotate270,
+            (true, 0) => Orientation::MirrorHorizontal,
+            (true, 90) => Orientation::Transverse,
+            (true, 180) => Orientation::MirrorVertical,
+            (true, 270) => Orientation::Transpose,
+            _ => return None,
+        })
+    }
+
+    /// Clockwise rotation in degrees, applied after the mirror
+    pub fn rotation(self) -> u32 {
+        match self {
+            Orientation::Normal | Orientation::MirrorHorizontal => 0,
+            Orientation::Rotate90 | Orientation::Transverse => 90,
+            Orientation::Rotate180 | Orientation::MirrorVertical => 180,
+            Orientation::Rotate270 | Orientation::Transpose => 270,
+        }
+    }
+
+    /// Whether the pixels need to be mirrored left to right before the rotation
+    pub fn is_mirrored(self) -> bool {
+        matches!(self, Orientation::MirrorHorizontal | Orientation::MirrorVertical |
+            Orientation::Transpose | Orientation::Transverse)
+    }
+
+    /// Whether width and height are swapped in the displayed image
+    pub fn swaps_dimensions(self) -> bool {
+        matches!(self.rotation(), 90 | 270)
+    }
+
+    /// Width and height of the displayed image, given those of the stored pixels
+    pub fn output_dimensions(self, width: u32, height: u32) -> (u32, u32) {
+        if self.swaps_dimensions() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Position in the displayed image of the stored pixel at `x`, `y`, where
+    /// `width` and `height` are the dimensions of the stored pixels. `None` if the
+    /// pixel is outside of the image.
+    pub fn transform_point(self, x: u32, y: u32, width: u32, height: u32) -> Option<(u32, u32)> {
+        if x >= width || y >= height {
+            return None;
+        }
+        let (last_x, last_y) = (width - 1, height - 1);
+        let x = if self.is_mirrored() { last_x - x } else { x };
+        Some(match self.rotation() {
+            90 => (last_y - y, x),
+            180 => (last_x - x, last_y - y),
+            270 => (y, last_x - x),
+            _ => (x, y),
+        })
+    }
+
+    /// Single orientation equivalent to applying `self`, then `next`
+    pub fn then(self, next: Orientation) -> Orientation {
+        // A mirror reverses the direction of the rotation that precedes it
+        let rotation = if next.is_mirrored() { 360 - self.rotation() } else { self.rotation() };
+        Self::from_parts(self.is_mirrored() != next.is_mirrored(), rotation + next.rotation())
+            .unwrap_or(Orientation::Normal)
+    }
+
+    /// Orientation that undoes this one
+    pub fn inverse(self) -> Orientation {
+        if self.is_mirrored() {
+            self
+        } else {
+            Self::from_parts(false, 360 - self.rotation()).unwrap_or(Orientation::Normal)
+        }
+    }
+
+    /// Orientation to store after the pixels have been transformed by `applied`,
+    /// such that the displayed image stays the same.
+    ///
+    /// Applying the orientation itself to the pixels gives `Normal`.
+    pub fn after_transform(self, applied: Orientation) -> Orientation {
+        applied.inverse().then(self)
+    }
+
+    /// Orientation to store after the pixels have been rotated clockwise by
+    /// `degrees`, or `None` if that is not a multiple of 90°
+    pub fn after_rotation(self, degrees: u32) -> Option<Orientation> {
+        Some(self.after_transform(Self::from_parts(false, degrees)?))
+    }
+}
+
+impl From<Orientation> for u16 {
+    fn from(o: Orientation) -> u16 {
+        o as u16
+    }
+}
+
+impl ExifData {
+    /// Orientation of the main image, if the tag is present and valid
+    pub fn orientation(&self) -> Option<Orientation> {
+        let value = self.get_in(IfdKind::Ifd0, ExifTag::Orientation)?.value.to_u32(0)?;
+        Orientation::from_u16(u16::try_from(value).ok()?)
+    }
+}
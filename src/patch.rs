@@ -0,0 +1,104 @@
+use super::types::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Bytes to overwrite in a file, at `offset`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BytePatch {
+    pub offset: usize,
+    pub data: Vec<u8>,
+}
+
+/// Position of the 12-byte entry of each tag, by IFD, in the TIFF data it was
+/// parsed from. Tags found more than once in an IFD have no position.
+#[derive(Clone, Default)]
+pub(crate) struct EntryPositions(HashMap<(IfdKind, u16), Option<usize>>);
+
+impl EntryPositions {
+    pub(crate) fn insert(&mut self, kind: IfdKind, tag: u16, position: usize) {
+        self.0.entry((kind, tag)).and_modify(|p| *p = None).or_insert(Some(position));
+    }
+
+    pub(crate) fn get(&self, kind: IfdKind, tag: u16) -> Option<usize> {
+        self.0.get(&(kind, tag)).copied().flatten()
+    }
+}
+
+impl fmt::Debug for EntryPositions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "EntryPositions({} entries)", self.0.len())
+    }
+}
+
+impl ExifData {
+    /// Patches that turn the metadata of `original`, the file this was parsed
+    /// from, into this one, without moving anything else in the file. Vendor
+    /// data, such as MakerNotes, keeps working, since its offsets stay valid.
+    ///
+    /// Entries are located by IFD and tag through the positions recorded when
+    /// parsing, so tags found more than once in an IFD cannot be patched. A changed
+    /// value is written over the old one, and its unused bytes are zeroed, so it
+    /// must not be longer than the old value, unless both fit in the IFD entry.
+    /// Returns `None` when that is not the case, or when entries were added or
+    /// removed; the file must then be re-serialized, as `write_patched()` does.
+    pub fn patches(&self, original: &[u8]) -> Result<Option<Vec<BytePatch>>, ExifError> {
+        let parsed = crate::parse_buffer_quiet(original).0?;
+        if parsed.le != self.le || parsed.tiff_offset != self.tiff_offset || parsed.entries.len() != self.entries.len() {
+            return Ok(None);
+        }
+
+        let mut patches = vec![];
+        let mut patched = HashSet::new();
+        for entry in &self.entries {
+            // Each entry of the file is patched at most once
+            let position = match self.positions.get(entry.kind, entry.ifd.tag) {
+                Some(position) if parsed.positions.get(entry.kind, entry.ifd.tag) == Some(position) => position,
+                _ => return Ok(None),
+            };
+            if !patched.insert(position) {
+                return Ok(None);
+            }
+            let old = match parsed.entries.iter().find(|e| e.kind == entry.kind && e.ifd.tag == entry.ifd.tag) {
+                Some(old) => &old.ifd,
+                None => return Ok(None),
+            };
+            match self.patch_entry(position, old, &entry.ifd) {
+                Some(entry_patches) => patches.extend(entry_patches),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(patches))
+    }
+
+    /// Patches that turn the IFD entry `old`, at `position`, into `new`, if `new` fits
+    fn patch_entry(&self, position: usize, old: &IfdEntry, new: &IfdEntry) -> Option<Vec<BytePatch>> {
+        if old.format == new.format && old.count == new.count && old.data == new.data {
+            return Some(vec![]);
+        }
+        if new.data.len() > 4 && new.data.len() > old.data.len() {
+            return None;
+        }
+
+        // Format and count, then the value or the offset to it
+        let mut header = vec![];
+        if self.le {
+            header.extend(&(new.format as u16).to_le_bytes());
+            header.extend(&new.count.to_le_bytes());
+        } else {
+            header.extend(&(new.format as u16).to_be_bytes());
+            header.extend(&new.count.to_be_bytes());
+        }
+        let mut patches = vec![];
+        if new.data.len() <= 4 {
+            header.extend(&new.data);
+            header.resize(10, 0);
+        } else {
+            let mut data = new.data.clone();
+            data.resize(old.data.len(), 0);
+            patches.push(BytePatch { offset: self.file_offset(old.try_data_as_offset()?), data });
+            header.extend(&old.ifd_data);
+        }
+        patches.insert(0, BytePatch { offset: self.file_offset(position + 2), data: header });
+        Some(patches)
+    }
+}
@@ -0,0 +1,90 @@
+use super::types::*;
+
+/// Metadata removed by `ExifData::redact()`
+#[derive(Clone, Debug, PartialEq)]
+pub enum Redaction {
+    /// Every entry
+    All,
+    /// The GPS IFD, together with the GPSOffset pointer to it
+    Gps,
+    /// Serial numbers, unique IDs, the owner name, the MakerNote and the thumbnail
+    Identifiers,
+    /// The entries of these tags, in any IFD. A sub-IFD pointer tag stands for
+    /// the whole sub-IFD.
+    Tags(Vec<ExifTag>),
+}
+
+/// Options of `ExifData::redact()`
+#[derive(Clone, Debug, PartialEq)]
+pub struct RedactOptions {
+    pub redaction: Redaction,
+    /// Keep the Orientation tag, whatever the redaction, so that the image still
+    /// displays upright. The orientation is not baked into the pixels: that would
+    /// take decoding and re-encoding the image, which this crate does not do. With
+    /// `Redaction::All`, IFD-0 is left with this one entry.
+    pub keep_orientation_tag: bool,
+}
+
+impl RedactOptions {
+    pub fn new(redaction: Redaction) -> Self {
+        RedactOptions { redaction, keep_orientation_tag: false }
+    }
+
+    pub fn keep_orientation_tag(self, keep_orientation_tag: bool) -> Self {
+        RedactOptions { keep_orientation_tag, ..self }
+    }
+}
+
+/// Tags that identify the camera, the lens, the owner or the picture
+const IDENTIFIERS: &[ExifTag] = &[
+    ExifTag::BodySerialNumber,
+    ExifTag::LensSerialNumber,
+    ExifTag::CameraSerialNumber,
+    ExifTag::ImageUniqueID,
+    ExifTag::RawDataUniqueID,
+    ExifTag::CameraOwnerName,
+    ExifTag::MakerNote,
+    ExifTag::MakerNoteSafety,
+    ExifTag::JPEGInterchangeFormat,
+    ExifTag::JPEGInterchangeFormatLength,
+];
+
+impl ExifData {
+    /// Removes the metadata selected by `options`, and returns the removed entries.
+    /// Sub-IFD pointers are dropped with their last entry, and not returned.
+    ///
+    /// Only the parsed entries change; write them back with `write_jpeg()`,
+    /// `write_png()`, `write_webp()` or `write_tiff()`, which leave the pixel data
+    /// alone. `write_tiff()` zeroes the old IFD-0 and its sub-IFDs, but keeps the
+    /// IFDs after IFD-0, such as the thumbnail, in the file.
+    pub fn redact(&mut self, options: &RedactOptions) -> Vec<ExifEntry> {
+        let redacted = |e: &ExifEntry| {
+            if options.keep_orientation_tag && e.kind == IfdKind::Ifd0 && e.tag == ExifTag::Orientation {
+                return false;
+            }
+            match options.redaction {
+                Redaction::All => true,
+                Redaction::Gps => e.kind == IfdKind::Gps,
+                // The thumbnail is in IFD-1
+                Redaction::Identifiers => {
+                    matches!(e.kind, IfdKind::Ifd1 | IfdKind::Makernote) || IDENTIFIERS.contains(&e.tag)
+                },
+                Redaction::Tags(ref tags) => tags.contains(&e.tag) || tags.iter().any(|&tag| in_sub_ifd(e.kind, tag)),
+            }
+        };
+        let (removed, kept) = self.entries.drain(..).partition(redacted);
+        self.entries = kept;
+        self.refresh();
+        removed
+    }
+}
+
+/// Whether an entry of IFD `kind` is in the sub-IFD that `pointer` points to
+fn in_sub_ifd(kind: IfdKind, pointer: ExifTag) -> bool {
+    match pointer {
+        ExifTag::ExifOffset => kind == IfdKind::Exif || kind == IfdKind::Interoperability,
+        ExifTag::GPSOffset => kind == IfdKind::Gps,
+        ExifTag::InteropOffset => kind == IfdKind::Interoperability,
+        _ => false,
+    }
+}
@@ -0,0 +1,133 @@
+use super::lowlevel::read_u16_array;
+use super::types::*;
+use encoding_rs::{EUC_JP, ISO_2022_JP, SHIFT_JIS};
+
+/// "ASCII\0\0\0"
+const ASCII_CODE: [u8; 8] = [0x41, 0x53, 0x43, 0x49, 0x49, 0, 0, 0];
+/// "JIS\0\0\0\0\0"
+const JIS_CODE: [u8; 8] = [0x4a, 0x49, 0x53, 0, 0, 0, 0, 0];
+/// "UNICODE\0"
+const UNICODE_CODE: [u8; 8] = [0x55, 0x4e, 0x49, 0x43, 0x4f, 0x44, 0x45, 0x00];
+/// Eight zero bytes, meaning the encoding is not specified
+const UNDEFINED_CODE: [u8; 8] = [0; 8];
+
+/// Character code declared in the 8-byte preamble of an Undefined tag that contains
+/// text, like UserComment, GPSProcessingMethod and GPSAreaInformation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TextEncoding {
+    /// ITU-T T.50 IA5, i.e. ASCII
+    Ascii,
+    /// JIS X 0208. Found in the wild as ISO-2022-JP, Shift-JIS or EUC-JP.
+    Jis,
+    /// UCS-2/UTF-16, of either byte order
+    Unicode,
+    /// No character code specified
+    Undefined,
+}
+
+/// Text decoded from a tag whose character code is given by its first 8 bytes
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncodedText {
+    /// Character code declared by the tag
+    pub encoding: TextEncoding,
+    /// Decoded text, without trailing NUL padding
+    pub text: String,
+}
+
+impl TagValue {
+    /// Decode an Undefined value that starts with an 8-byte character code, as used by
+    /// UserComment, GPSProcessingMethod and GPSAreaInformation.
+    ///
+    /// Returns `None` if this is not an Undefined value, or if the character code is
+    /// missing or not one of those defined by the Exif standard.
+    pub fn to_encoded_text(&self) -> Option<EncodedText> {
+        match *self {
+            TagValue::Undefined(ref v, le) => {
+                let (code, data) = (v.get(..8)?, v.get(8..)?);
+                let (encoding, text) = if code == ASCII_CODE {
+                    (TextEncoding::Ascii, String::from_utf8_lossy(trim_nul(data)).into_owned())
+                } else if code == JIS_CODE {
+                    (TextEncoding::Jis, decode_jis(trim_nul(data)))
+                } else if code == UNICODE_CODE {
+                    (TextEncoding::Unicode, decode_unicode(data, le)?)
+                } else if code == UNDEFINED_CODE {
+                    (TextEncoding::Undefined, String::from_utf8_lossy(trim_nul(data)).into_owned())
+                } else {
+                    return None;
+                };
+                Some(EncodedText { encoding, text })
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Remove NUL padding at the end
+fn trim_nul(mut data: &[u8]) -> &[u8] {
+    while let Some((&0, rest)) = data.split_last() {
+        data = rest;
+    }
+    data
+}
+
+/// The Exif standard names JIS X 0208 but does not say how it is serialized, so the
+/// byte stream is checked against the encodings cameras are known to use. Bare
+/// two-byte JIS codes are not decoded: they cannot be told apart from ASCII text.
+fn decode_jis(data: &[u8]) -> String {
+    if data.contains(&0x1b) {
+        // Escape sequences switch between ASCII and JIS X 0208
+        return ISO_2022_JP.decode_without_bom_handling(data).0.into_owned();
+    }
+
+    // Plain ASCII is valid in both, and is left to the fallback
+    if !data.is_ascii() {
+        if let Some(s) = SHIFT_JIS.decode_without_bom_handling_and_without_replacement(data) {
+            return s.into_owned();
+        }
+        if let Some(s) = EUC_JP.decode_without_bom_handling_and_without_replacement(data) {
+            return s.into_owned();
+        }
+    }
+    String::from_utf8_lossy(data).into_owned()
+}
+
+/// UCS-2 should follow the byte order of the TIFF container, but some cameras use
+/// the opposite order, with or without a byte order mark.
+fn decode_unicode(data: &[u8], le: bool) -> Option<String> {
+    let (data, le) = match data {
+        [0xfe, 0xff, rest @ ..] => (rest, false),
+        [0xff, 0xfe, rest @ ..] => (rest, true),
+        _ => (data, guess_utf16_le(data).unwrap_or(le)),
+    };
+
+    let v16 = read_u16_array(le, (data.len() / 2) as u32, data)?;
+    let mut v16 = &v16[..];
+    while let Some((&0, rest)) = v16.split_last() {
+        v16 = rest;
+    }
+    Some(String::from_utf16_lossy(v16))
+}
+
+/// Text in Latin scripts has mostly zero high bytes, which tells the byte order away.
+/// Returns `None` if there is no clear majority.
+fn guess_utf16_le(data: &[u8]) -> Option<bool> {
+    let pairs = data.chunks_exact(2).filter(|p| p != &[0, 0]);
+    let (mut zero_first, mut zero_second) = (0, 0);
+    for p in pairs {
+        if let [first, second] = *p {
+            if first == 0 {
+                zero_first += 1;
+            }
+            if second == 0 {
+                zero_second += 1;
+            }
+        }
+    }
+    if zero_second > zero_first * 2 {
+        Some(true)
+    } else if zero_first > zero_second * 2 {
+        Some(false)
+    } else {
+        None
+    }
+}
@@ -3,6 +3,8 @@ use super::exif::*;
 use super::exifpost::*;
 use super::ifdformat::*;
 use super::lowlevel::*;
+use super::options::*;
+use super::patch::EntryPositions;
 use super::types::*;
 
 type InExifResult = Result<(), ExifError>;
@@ -10,8 +12,17 @@ type InExifResult = Result<(), ExifError>;
 /// Parse of raw IFD entry into EXIF data, if it is of a known type, and returns
 /// an ExifEntry object. If the tag is unknown, the enumeration is set to UnknownToMe,
 /// but the raw information of tag is still available in the ifd member.
-pub(crate) fn parse_exif_entry(ifd: IfdEntry, warnings: &mut Vec<String>, kind: IfdKind) -> ExifEntry {
-    let (tag, unit, format, min_count, max_count, more_readable) = tag_to_exif(ifd.tag);
+///
+/// `index` and `offset` are the position of the entry in its IFD and in the TIFF data,
+/// for warnings.
+pub(crate) fn parse_exif_entry(
+    ifd: IfdEntry,
+    warnings: &mut Vec<ParseWarning>,
+    kind: IfdKind,
+    index: usize,
+    offset: usize,
+) -> ExifEntry {
+    let (tag, unit, format, min_count, max_count, more_readable) = tag_to_exif(kind, ifd.tag);
     let value = match tag_value_new(&ifd) {
         Some(v) => v,
         None => TagValue::Invalid(ifd.data.clone(), ifd.le, ifd.format as u16, ifd.count),
@@ -29,35 +40,50 @@ pub(crate) fn parse_exif_entry(ifd: IfdEntry, warnings: &mut Vec<String>, kind:
 
     if tag == ExifTag::UnknownToMe {
         // Unknown EXIF tag type
+        warnings.push(ParseWarning::UnknownTag { tag: e.ifd.tag, kind, format: e.ifd.format, index, offset });
         return e;
     }
 
-    // Internal assert:
+    // Internal assert, about the tag tables rather than the input:
     // 1) tag must match enum
     // 2) all types except Ascii, Undefined, Unknown must have definite length
     // 3) Str type must not have a definite length
-    if (((tag as u32) & 0xffff) as u16) != e.ifd.tag
-        || (min_count == -1
-            && (format != IfdFormat::Ascii
-                && format != IfdFormat::Undefined
-                && format != IfdFormat::Unknown))
-        || (min_count != -1 && format == IfdFormat::Ascii)
-    {
-        panic!("Internal error {:x}", e.ifd.tag);
-    }
-
-    if format != e.ifd.format {
-        warnings.push(format!(
-            "EXIF tag {:x} {} ({}), expected format {} ({:?}), found {} ({:?})",
-            e.ifd.tag, e.ifd.tag, tag, format as u8, format, e.ifd.format as u8, e.ifd.format
-        ));
+    debug_assert!(
+        (((tag as u32) & 0xffff) as u16) == e.ifd.tag
+            && (min_count != -1
+                || format == IfdFormat::Ascii
+                || format == IfdFormat::Undefined
+                || format == IfdFormat::Unknown)
+            && (min_count == -1 || format != IfdFormat::Ascii),
+        "Internal error {:x}", e.ifd.tag
+    );
+
+    // Exif 3.0 allows UTF-8 wherever ASCII is expected
+    let utf8_for_ascii = format == IfdFormat::Ascii && e.ifd.format == IfdFormat::Utf8;
+    if format != e.ifd.format && !utf8_for_ascii && !alternative_formats(tag).contains(&e.ifd.format) {
+        warnings.push(ParseWarning::WrongFormat {
+            tag: e.ifd.tag,
+            exif_tag: tag,
+            kind,
+            expected: format,
+            found: e.ifd.format,
+            index,
+            offset,
+        });
     }
 
     if min_count != -1 && ((e.ifd.count as i32) < min_count || (e.ifd.count as i32) > max_count) {
-        warnings.push(format!(
-            "EXIF tag {:x} {} ({:?}), format {}, expected count {}..{} found {}",
-            e.ifd.tag, e.ifd.tag, tag, format as u8, min_count, max_count, e.ifd.count
-        ));
+        warnings.push(ParseWarning::CountOutOfRange {
+            tag: e.ifd.tag,
+            exif_tag: tag,
+            kind,
+            format,
+            min: min_count as u32,
+            max: max_count as u32,
+            found: e.ifd.count,
+            index,
+            offset,
+        });
     }
     e
 }
@@ -73,11 +99,11 @@ pub fn parse_ifd(
 
     for i in 0..count {
         let mut offset = (i as usize) * 12;
-        let tag = read_u16(le, &contents.get(offset..)?)?;
+        let tag = read_u16(le, contents.get(offset..)?)?;
         offset += 2;
-        let format = read_u16(le, &contents.get(offset..)?)?;
+        let format = read_u16(le, contents.get(offset..)?)?;
         offset += 2;
-        let count = read_u32(le, &contents.get(offset..)?)?;
+        let count = read_u32(le, contents.get(offset..)?)?;
         offset += 4;
         let data = contents.get(offset..offset + 4)?.to_vec();
 
@@ -97,144 +123,234 @@ pub fn parse_ifd(
     let next_ifd = if subifd {
         0
     } else {
-        read_u32(le, &contents[count as usize * 12..])? as usize
+        read_u32(le, contents.get(count as usize * 12..)?)? as usize
     };
 
     Some((entries, next_ifd))
 }
 
+/// Warnings, and the resources used so far against the limits of the options
+struct ParseState<'a> {
+    options: &'a ParseOptions,
+    warnings: &'a mut Vec<ParseWarning>,
+    positions: &'a mut EntryPositions,
+    /// Position of the 12-byte entry of each ExifEntry read so far, in order
+    offsets: Vec<usize>,
+    entries: usize,
+    bytes: usize,
+    ifds: usize,
+}
+
+impl ParseState<'_> {
+    fn enter_ifd(&mut self, kind: IfdKind, offset: usize, depth: usize) -> InExifResult {
+        if depth > self.options.max_ifd_depth {
+            return Err(ExifError::IfdTooDeep { kind, offset, limit: self.options.max_ifd_depth });
+        }
+        self.ifds += 1;
+        if self.ifds > self.options.max_ifds {
+            return Err(ExifError::TooManyIfds { limit: self.options.max_ifds });
+        }
+        Ok(())
+    }
+
+    fn add_entries(&mut self, count: usize) -> InExifResult {
+        self.entries += count;
+        if self.entries > self.options.max_entries {
+            return Err(ExifError::TooManyEntries { limit: self.options.max_entries });
+        }
+        Ok(())
+    }
+
+    fn add_value(&mut self, kind: IfdKind, tag: u16, length: usize, offset: usize) -> InExifResult {
+        if length > self.options.max_value_bytes {
+            return Err(ExifError::ValueTooLarge { kind, tag, length, limit: self.options.max_value_bytes, offset });
+        }
+        self.bytes += length;
+        if self.bytes > self.options.max_total_bytes {
+            return Err(ExifError::TooMuchData { limit: self.options.max_total_bytes });
+        }
+        Ok(())
+    }
+}
+
 /// Deep parse of IFD that grabs EXIF data from IFD0, SubIFD and GPS IFD
 fn parse_exif_ifd(
     le: bool,
     contents: &[u8],
     ioffset: usize,
     exif_entries: &mut Vec<ExifEntry>,
-    warnings: &mut Vec<String>,
+    state: &mut ParseState<'_>,
     kind: IfdKind,
+    depth: usize,
 ) -> InExifResult {
     let mut offset = ioffset;
+    let truncated = || ExifError::IfdTruncated { kind, offset: ioffset };
+
+    state.enter_ifd(kind, ioffset, depth)?;
 
     if contents.len() < (offset + 2) {
-        return Err(ExifError::ExifIfdTruncated(
-            format!("Truncated {:?} at dir entry count ({} < {})", kind, contents.len(), (offset + 2)),
-        ));
+        return Err(ExifError::ExifIfdTruncated {
+            kind,
+            offset: ioffset,
+            reason: format!("Truncated {:?} at dir entry count ({} < {})", kind, contents.len(), (offset + 2)),
+        });
     }
 
     let count = read_u16(
         le,
-        &contents
+        contents
             .get(offset..)
-            .ok_or(ExifError::IfdTruncated)?,
-    ).ok_or(ExifError::IfdTruncated)?;
-    let ifd_length = (count as usize) * 12;
+            .ok_or_else(truncated)?,
+    ).ok_or_else(truncated)?;
     offset += 2;
 
-    if contents.len() < (offset + ifd_length) {
-        return Err(ExifError::ExifIfdTruncated(
-            "Truncated at dir listing".to_string(),
-        ));
+    // Keep the entries that fit in the data
+    let available = ((contents.len() - offset) / 12).min(count as usize) as u16;
+    if available < count {
+        state.warnings.push(ParseWarning::IfdTruncated { kind, declared: count, read: available, offset: ioffset });
     }
+    let count = available;
+    let ifd_length = (count as usize) * 12;
+    state.add_entries(count as usize)?;
 
     let ifd_content = &contents
         .get(offset..offset + ifd_length)
-        .ok_or(ExifError::IfdTruncated)?;
-    let (ifd, _) = parse_ifd(true, le, count, ifd_content).ok_or(ExifError::IfdTruncated)?;
-
-    for mut entry in ifd {
+        .ok_or_else(truncated)?;
+    let (ifd, _) = parse_ifd(true, le, count, ifd_content).ok_or_else(truncated)?;
+
+    for (i, mut entry) in ifd.into_iter().enumerate() {
+        let entry_offset = offset + i * 12;
+        // Data that cannot be in the file is not copied, so it costs nothing
+        let length = entry.length();
+        if length <= contents.len() {
+            state.add_value(kind, entry.tag, length, entry_offset)?;
+        }
         if !entry.copy_data(contents) {
-            // data is probably beyond EOF
+            state.warnings.push(ParseWarning::DataBeyondEof {
+                tag: entry.tag,
+                kind,
+                data_offset: entry.try_data_as_offset().unwrap_or(0),
+                length,
+                index: i,
+                offset: entry_offset,
+            });
             continue;
         }
-        let exif_entry = parse_exif_entry(entry, warnings, kind);
+        state.positions.insert(kind, entry.tag, entry_offset);
+        state.offsets.push(entry_offset);
+        let exif_entry = parse_exif_entry(entry, state.warnings, kind, i, entry_offset);
         exif_entries.push(exif_entry);
     }
 
     Ok(())
 }
 
-/// Parses IFD0 and looks for SubIFD or GPS IFD within IFD0
+/// Parses a sub-IFD pointed to from another IFD. If it cannot be parsed, it is
+/// recorded as skipped in the warnings, and the entries read so far are kept.
+/// Going over a limit is still an error. `pointer` is the position of the entry
+/// pointing to the IFD, and the offset in it if it is a valid pointer.
+fn parse_sub_ifd(
+    le: bool,
+    contents: &[u8],
+    pointer: (usize, Option<usize>),
+    exif_entries: &mut Vec<ExifEntry>,
+    state: &mut ParseState<'_>,
+    kind: IfdKind,
+    depth: usize,
+) -> InExifResult {
+    let offset = match pointer {
+        (_, Some(offset)) => offset,
+        (position, None) => {
+            state.warnings.push(ParseWarning::IfdSkipped { kind, reason: "Invalid IFD pointer".to_string(), offset: position });
+            return Ok(());
+        },
+    };
+    let result = if offset >= contents.len() {
+        Err(ExifError::ExifIfdTruncated { kind, offset, reason: "IFD offset goes past EOF".to_string() })
+    } else {
+        parse_exif_ifd(le, contents, offset, exif_entries, state, kind, depth)
+    };
+    // The offset is part of the warning already
+    let reason = match result {
+        Ok(()) => return Ok(()),
+        Err(ExifError::ExifIfdTruncated { reason, .. }) => reason,
+        Err(ExifError::IfdTruncated { .. }) => "TIFF IFD truncated".to_string(),
+        Err(e) => return Err(e),
+    };
+    state.warnings.push(ParseWarning::IfdSkipped { kind, reason, offset });
+    Ok(())
+}
+
+/// Parses IFD0 and the Exif, GPS and Interoperability IFDs that hang from it.
+/// Only a broken IFD0, or going over one of the limits of `options`, is an error;
+/// problems with the other IFDs are warnings.
 pub fn parse_ifds(
     le: bool,
     ifd0_offset: usize,
     contents: &[u8],
-    warnings: &mut Vec<String>,
+    options: &ParseOptions,
+    warnings: &mut Vec<ParseWarning>,
+    positions: &mut EntryPositions,
 ) -> ExifEntryResult {
-    let mut offset = ifd0_offset;
     let mut exif_entries: Vec<ExifEntry> = Vec::new();
+    let mut state = ParseState { options, warnings, positions, offsets: Vec::new(), entries: 0, bytes: 0, ifds: 0 };
 
     // fills exif_entries with data from IFD0
-
-    match parse_exif_ifd(le, contents, offset, &mut exif_entries, warnings, IfdKind::Ifd0) {
-        Ok(_) => true,
-        Err(e) => return Err(e),
-    };
-
-    // at this point we knot that IFD0 is good
-    // looks for SubIFD (EXIF)
-
-    let count = read_u16(
-        le,
-        &contents
-            .get(offset..offset + 2)
-            .ok_or(ExifError::IfdTruncated)?,
-    ).ok_or(ExifError::IfdTruncated)?;
-    let ifd_length = (count as usize) * 12 + 4;
-    offset += 2;
-
-    let ifd_content = &contents
-        .get(offset..offset + ifd_length)
-        .ok_or(ExifError::IfdTruncated)?;
-    let (ifd, _) = parse_ifd(false, le, count, ifd_content).ok_or(ExifError::IfdTruncated)?;
-
-    for entry in &ifd {
-        // Identify which IFD this entry belongs to (IFD-0, Exif, Gps, IFD-1 etc)
-        let ifd_kind = if entry.tag == (((ExifTag::ExifOffset as u32) & 0xffff) as u16) {
-            IfdKind::Exif
-        } else if entry.tag == (((ExifTag::GPSOffset as u32) & 0xffff) as u16) {
-            // Gps
-            IfdKind::Gps
-        } else {
-            continue;
-        };
-
-        let exif_offset = entry.try_data_as_offset().unwrap_or(!0);
-        if contents.len() < exif_offset {
-            return Err(ExifError::ExifIfdTruncated(
-                "Exif SubIFD goes past EOF".to_string(),
-            ));
-        }
-        parse_exif_ifd(le, contents, exif_offset, &mut exif_entries, warnings, ifd_kind)?;
+    parse_exif_ifd(le, contents, ifd0_offset, &mut exif_entries, &mut state, IfdKind::Ifd0, 0)?;
+
+    // at this point we know that IFD0 is good. A broken sub-IFD only loses its own
+    // entries; the caller decides whether that is acceptable.
+    let sub_ifds: Vec<(IfdKind, (usize, Option<usize>))> = exif_entries.iter().zip(&state.offsets)
+        .filter_map(|(e, &position)| match e.tag {
+            ExifTag::ExifOffset => Some((IfdKind::Exif, (position, e.ifd.try_data_as_offset()))),
+            ExifTag::GPSOffset => Some((IfdKind::Gps, (position, e.ifd.try_data_as_offset()))),
+            _ => None,
+        })
+        .collect();
+    for (kind, pointer) in sub_ifds {
+        parse_sub_ifd(le, contents, pointer, &mut exif_entries, &mut state, kind, 1)?;
     }
 
-    for n in 0..exif_entries.len() {
-        let (begin, end) = exif_entries.split_at_mut(n);
-        let (entry, end) = end.split_first_mut().unwrap();
-        exif_postprocessing(entry, begin, end);
+    // The Interoperability IFD hangs from the Exif IFD
+    let interop_pointer = exif_entries.iter().zip(&state.offsets)
+        .find(|(e, _)| e.kind == IfdKind::Exif && e.tag == ExifTag::InteropOffset)
+        .map(|(e, &position)| (position, e.ifd.try_data_as_offset()));
+    if let Some(interop_pointer) = interop_pointer {
+        parse_sub_ifd(le, contents, interop_pointer, &mut exif_entries, &mut state, IfdKind::Interoperability, 2)?;
     }
 
+    exif_postprocessing_all(&mut exif_entries);
+
     Ok(exif_entries)
 }
 
-/// Parse a TIFF image, or embedded TIFF in JPEG, in order to get IFDs and then the EXIF data
-pub fn parse_tiff(contents: &[u8], warnings: &mut Vec<String>) -> (ExifEntryResult, bool) {
-    let mut le = false;
-
+/// Parse a TIFF image, or embedded TIFF in JPEG, in order to get IFDs and then the EXIF data.
+/// The position of each entry is recorded in `positions`.
+pub fn parse_tiff(
+    contents: &[u8],
+    options: &ParseOptions,
+    warnings: &mut Vec<ParseWarning>,
+    positions: &mut EntryPositions,
+) -> (ExifEntryResult, bool) {
     if contents.len() < 8 {
         return (Err(ExifError::TiffTruncated), false);
-    } else if contents[0] == b'I' && contents[1] == b'I' && contents[2] == 42 && contents[3] == 0 {
+    }
+    let le = match *contents {
         /* TIFF little-endian */
-        le = true;
-    } else if contents[0] == b'M' && contents[1] == b'M' && contents[2] == 0 && contents[3] == 42 {
+        [b'I', b'I', 42, 0, ..] => true,
         /* TIFF big-endian */
-    } else {
-        let err = format!(
-            "Preamble is {:x} {:x} {:x} {:x}",
-            contents[0], contents[1], contents[2], contents[3]
-        );
-        return (Err(ExifError::TiffBadPreamble(err)), false);
-    }
+        [b'M', b'M', 0, 42, ..] => false,
+        [b0, b1, b2, b3, ..] => {
+            let err = format!("Preamble is {:x} {:x} {:x} {:x}", b0, b1, b2, b3);
+            return (Err(ExifError::TiffBadPreamble(err)), false);
+        },
+        _ => return (Err(ExifError::TiffTruncated), false),
+    };
 
-    let offset = read_u32(le, &contents[4..]).unwrap() as usize;
+    let offset = match contents.get(4..).and_then(|c| read_u32(le, c)) {
+        Some(offset) => offset as usize,
+        None => return (Err(ExifError::TiffTruncated), le),
+    };
 
-    (parse_ifds(le, offset, &contents, warnings), le)
+    (parse_ifds(le, offset, contents, options, warnings, positions), le)
 }
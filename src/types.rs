@@ -1,7 +1,9 @@
 use std::borrow::Cow;
 use std::fmt;
 use std::io;
+use super::access::TagIndex;
 use super::ifdformat::tag_value_eq;
+use super::patch::EntryPositions;
 use super::rational::*;
 
 /// The value of the Exif header.
@@ -11,32 +13,96 @@ const MOTOROLA_TIFF_HEADER: &[u8] = &[b'M', b'M', 0x00, 0x2a];
 const DATA_WIDTH: usize = 4;
 
 /// Top-level structure that contains all parsed metadata inside an image
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub struct ExifData {
     /// MIME type of the parsed image. It may be "image/jpeg", "image/tiff", or empty if unrecognized.
     pub mime: &'static str,
     /// Collection of EXIF entries found in the image
     pub entries: Vec<ExifEntry>,
     /// If `true`, this uses little-endian byte ordering for the raw bytes. Otherwise, it uses big-endian ordering.
+    /// Changing it does not convert the raw bytes; see `set_byte_order()`.
     pub le: bool,
+    /// Position of the TIFF header in the parsed file. Offsets stored in IFD entries
+    /// are relative to it; see `file_offset()`.
+    pub(crate) tiff_offset: usize,
+    /// Where the entries were in the parsed file, for `patches()`
+    pub(crate) positions: EntryPositions,
+    /// Lookup table for `get()` and related methods, rebuilt whenever the entries
+    /// are changed through `set()`, `remove()` and the like
+    pub(crate) index: TagIndex,
 }
 
 impl ExifData {
     pub fn new(mime: &'static str, entries: Vec<ExifEntry>, le: bool) -> Self {
         ExifData {
             mime,
+            tiff_offset: 0,
+            positions: EntryPositions::default(),
+            index: TagIndex::new(&entries),
             entries,
             le,
         }
     }
 }
 
+/// Byte order of the numbers in TIFF data
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ByteOrder {
+    /// "II", Intel
+    LittleEndian,
+    /// "MM", Motorola
+    BigEndian,
+}
+
+impl ByteOrder {
+    pub fn is_le(self) -> bool {
+        self == ByteOrder::LittleEndian
+    }
+}
+
+impl ExifData {
+    /// Byte order of the entries, the same as `le`
+    pub fn byte_order(&self) -> ByteOrder {
+        if self.le { ByteOrder::LittleEndian } else { ByteOrder::BigEndian }
+    }
+}
+
 impl ExifData {
-    /// Serialize the metadata entries, and return the result.
+    /// Converts an offset within the TIFF data, such as the one in an IFD entry, to
+    /// a position in the parsed file
+    pub fn file_offset(&self, tiff_offset: usize) -> usize {
+        self.tiff_offset + tiff_offset
+    }
+}
+
+/// Compares the metadata, not where it was in the parsed file: equal values parsed
+/// from different files may have different `file_offset()`s, and patch different
+/// positions.
+impl PartialEq for ExifData {
+    fn eq(&self, other: &Self) -> bool {
+        self.mime == other.mime && self.entries == other.entries && self.le == other.le
+    }
+}
+
+impl ExifData {
+    /// Serialize the metadata entries, and return the result. For a JPEG, the result
+    /// starts with the Exif header, and is the payload of the APP1 segment. For other
+    /// images, such as PNG and WebP, it is the TIFF data alone.
     ///
     /// *Note*: this serializes the metadata according to its original endianness (specified
-    /// through the `le` attribute).
+    /// through the `le` attribute). Use `set_byte_order()` to change it.
     pub fn serialize(&self) -> Result<Vec<u8>, ExifError> {
+        let serialized = self.serialize_tiff()?;
+        Ok(if self.mime == "image/jpeg" {
+            [EXIF_HEADER, &serialized].concat()
+        } else {
+            serialized
+        })
+    }
+
+    /// Serialize the metadata entries as TIFF data, without the Exif header, whatever
+    /// the image type. This is the payload of the PNG `eXIf` and WebP `EXIF` chunks.
+    pub fn serialize_tiff(&self) -> Result<Vec<u8>, ExifError> {
         // Select the right TIFF header based on the endianness.
         let tiff_header = if self.le {
             INTEL_TIFF_HEADER
@@ -59,10 +125,18 @@ impl ExifData {
         };
         serialized.extend(&offset);
 
+        self.serialize_ifds(&mut serialized, 0, 0)?;
+        Ok(serialized)
+    }
+
+    /// Serialize IFD-0 and its sub-IFDs at the end of `serialized`, whose first byte
+    /// is at position `base` in the file. IFD-0 links to the IFD at `next_ifd`, if not 0.
+    pub(crate) fn serialize_ifds(&self, serialized: &mut Vec<u8>, base: usize, next_ifd: u32) -> Result<(), ExifError> {
         let mut ifd0 = vec![];
         let mut ifd1 = vec![];
         let mut exif = vec![];
         let mut gps = vec![];
+        let mut interop = vec![];
 
         for e in &self.entries {
             match e.kind {
@@ -70,15 +144,18 @@ impl ExifData {
                 IfdKind::Ifd1 => ifd1.push(e),
                 IfdKind::Exif => exif.push(e),
                 IfdKind::Gps => gps.push(e),
-                _ => {
-                    // XXX Silently ignore Makernote and Interoperability IFDs
+                IfdKind::Interoperability => interop.push(e),
+                IfdKind::Makernote => {
+                    // XXX Silently ignore Makernote IFD
                 },
             }
         }
 
         // IFD-1 contains the thumbnail. For now, the parser discards IFD-1, so its serialization
         // has not yet been implemented.
-        assert!(ifd1.is_empty());
+        if let Some(entry) = ifd1.first() {
+            return Err(ExifError::TagNotInIfd { tag: entry.tag, kind: IfdKind::Ifd1 });
+        }
 
         // Serialize the number of directory entries in this IFD.
         if self.le {
@@ -97,7 +174,7 @@ impl ExifData {
         // These offsets will be filled out (patched) later.
         let mut data_patches = vec![];
         for entry in ifd0 {
-            entry.ifd.serialize(&mut serialized, &mut data_patches)?;
+            entry.ifd.serialize(serialized, &mut data_patches)?;
 
             // If IFD-0 points to an Exif/GPS sub-IFD, the offset of the sub-IFD must be serialized
             // inside IFD-0. Subtract `DATA_WIDTH` from the length, because the pointer to the
@@ -112,21 +189,21 @@ impl ExifData {
             }
         }
 
-        if ifd1.is_empty() {
-            serialized.extend(&[0, 0, 0, 0]);
+        // The pointer to the next IFD (which is just the offset of that IFD in the file).
+        if self.le {
+            serialized.extend(&next_ifd.to_le_bytes());
         } else {
-            // Otherwise, serialize the pointer to IFD-1 (which is just the offset of IFD-1 in the
-            // file).
-            unimplemented!("IFD-1");
+            serialized.extend(&next_ifd.to_be_bytes());
         }
 
         // Patch the offsets serialized above.
         for patch in &data_patches {
+            pad_to_word(serialized, base);
             // The position of the data pointed to by the IFD entries serialized above.
             let bytes = if self.le {
-                (serialized.len() as u32).to_le_bytes()
+                ((base + serialized.len()) as u32).to_le_bytes()
             } else {
-                (serialized.len() as u32).to_be_bytes()
+                ((base + serialized.len()) as u32).to_be_bytes()
             };
 
             serialized.extend(patch.data);
@@ -135,34 +212,41 @@ impl ExifData {
             }
         }
 
-        if !exif.is_empty() {
-            self.serialize_ifd(&mut serialized, exif, exif_ifd_pointer)?;
+        // A sub-IFD is written, even if empty, whenever its parent points to it, so that
+        // the pointer never dangles.
+        let mut interop_ifd_pointer = None;
+        if !exif.is_empty() || exif_ifd_pointer.is_some() {
+            interop_ifd_pointer = self.serialize_ifd(serialized, base, exif, exif_ifd_pointer)?;
         }
 
-        if !gps.is_empty() {
-            self.serialize_ifd(&mut serialized, gps, gps_ifd_pointer)?;
+        if !gps.is_empty() || gps_ifd_pointer.is_some() {
+            self.serialize_ifd(serialized, base, gps, gps_ifd_pointer)?;
         }
 
-        // TODO Makernote, Interoperability IFD, Thumbnail image
+        if !interop.is_empty() || interop_ifd_pointer.is_some() {
+            self.serialize_ifd(serialized, base, interop, interop_ifd_pointer)?;
+        }
 
-        Ok(if self.mime == "image/jpeg" {
-            [EXIF_HEADER, &serialized].concat()
-        } else {
-            serialized
-        })
+        // TODO Makernote, Thumbnail image
+
+        Ok(())
     }
 
-    /// Serialize GPS/Exif IFD entries.
+    /// Serialize GPS/Exif/Interoperability IFD entries.
+    ///
+    /// Returns the position of the Interoperability IFD pointer, if this IFD has one.
     fn serialize_ifd(
         &self,
         serialized: &mut Vec<u8>,
+        base: usize,
         entries: Vec<&ExifEntry>,
         pos: Option<usize>,
-    ) -> Result<(), ExifError> {
+    ) -> Result<Option<usize>, ExifError> {
+        pad_to_word(serialized, base);
         let bytes = if self.le {
-            (serialized.len() as u32).to_le_bytes()
+            ((base + serialized.len()) as u32).to_le_bytes()
         } else {
-            (serialized.len() as u32).to_be_bytes()
+            ((base + serialized.len()) as u32).to_be_bytes()
         };
 
         // Serialize the number of directory entries in this IFD
@@ -179,25 +263,38 @@ impl ExifData {
         }
 
         let mut data_patches = vec![];
+        let mut interop_ifd_pointer = None;
 
         for entry in entries {
             entry.ifd.serialize(serialized, &mut data_patches)?;
+
+            if entry.tag == ExifTag::InteropOffset {
+                interop_ifd_pointer = Some(serialized.len() - DATA_WIDTH);
+            }
         }
 
         serialized.extend(&[0, 0, 0, 0]);
         for patch in &data_patches {
+            pad_to_word(serialized, base);
             // The position of the data pointed to by the IFD entries serialized above.
             let bytes = if self.le {
-                (serialized.len() as u32).to_le_bytes()
+                ((base + serialized.len()) as u32).to_le_bytes()
             } else {
-                (serialized.len() as u32).to_be_bytes()
+                ((base + serialized.len()) as u32).to_be_bytes()
             };
             serialized.extend(patch.data);
             for (place, byte) in serialized.iter_mut().skip(patch.offset_pos as usize).zip(bytes.iter()) {
                 *place = *byte;
             }
         }
-        Ok(())
+        Ok(interop_ifd_pointer)
+    }
+}
+
+/// TIFF offsets must be even: values and IFDs start on a word boundary
+fn pad_to_word(serialized: &mut Vec<u8>, base: usize) {
+    if (base + serialized.len()) % 2 == 1 {
+        serialized.push(0);
     }
 }
 
@@ -209,7 +306,7 @@ pub(super) struct Patch<'a> {
 }
 
 impl Patch<'_> {
-    pub fn new(offset_pos: u32, data: &[u8]) -> Patch {
+    pub fn new(offset_pos: u32, data: &[u8]) -> Patch<'_> {
         Patch {
             offset_pos,
             data,
@@ -225,11 +322,113 @@ pub enum ExifError {
     JpegWithoutExif(String),
     TiffTruncated,
     TiffBadPreamble(String),
-    IfdTruncated,
-    ExifIfdTruncated(String),
+    /// An IFD, at `offset` in the file, is cut short
+    IfdTruncated { kind: IfdKind, offset: usize },
+    /// An IFD, at `offset` in the file, is cut short or points past the end of the data
+    ExifIfdTruncated { kind: IfdKind, offset: usize, reason: String },
     ExifIfdEntryNotFound,
     UnsupportedNamespace,
     MissingExifOffset,
+    /// The image does not conform to the standard, and strict parsing was asked for
+    NonConforming(ParseWarning),
+    /// More IFD entries than `ParseOptions::max_entries`
+    TooManyEntries { limit: usize },
+    /// The entry at `offset` in the file has more data than `ParseOptions::max_value_bytes`
+    ValueTooLarge { kind: IfdKind, tag: u16, length: usize, limit: usize, offset: usize },
+    /// More entry data than `ParseOptions::max_total_bytes`
+    TooMuchData { limit: usize },
+    /// The IFD at `offset` in the file is nested deeper than `ParseOptions::max_ifd_depth`
+    IfdTooDeep { kind: IfdKind, offset: usize, limit: usize },
+    /// More IFDs than `ParseOptions::max_ifds`
+    TooManyIfds { limit: usize },
+    /// The tag is unknown, or does not belong in the IFD
+    TagNotInIfd { tag: ExifTag, kind: IfdKind },
+    /// The value is of another format than the tag allows
+    WrongTagFormat { tag: ExifTag, expected: IfdFormat, found: IfdFormat },
+    /// The value has fewer or more elements than the tag allows
+    WrongTagCount { tag: ExifTag, min: u32, max: u32, found: u32 },
+    /// The value is out of the range of the tag
+    InvalidTagValue { tag: ExifTag, reason: String },
+    /// The image to write the EXIF data into is not a valid JPEG
+    InvalidJpeg(String),
+    /// The EXIF data, of `size` bytes with the Exif header, does not fit in a JPEG
+    /// segment, which holds at most 65533 bytes
+    JpegSegmentTooLarge { size: usize },
+    /// The EXIF data is in another byte order than the TIFF file to write it into
+    ByteOrderMismatch,
+    /// The TIFF file would grow past 4 GiB, the largest offset TIFF can store
+    TiffTooLarge { size: u64 },
+    /// The image to write the EXIF data into is not a valid PNG
+    InvalidPng(String),
+    /// The image to write the EXIF data into is not a valid WebP
+    InvalidWebp(String),
+    /// The entry has an unknown format, or a value that could not be parsed, so its
+    /// data cannot be converted to another byte order
+    UnknownFormat { kind: IfdKind, tag: u16 },
+}
+
+/// Non-fatal problems found while an image is parsed. `index` is the position of
+/// the entry concerned in its IFD, and `offset` its position in the file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseWarning {
+    /// The entry is stored in another format than the one the standard prescribes
+    WrongFormat {
+        tag: u16,
+        exif_tag: ExifTag,
+        kind: IfdKind,
+        expected: IfdFormat,
+        found: IfdFormat,
+        index: usize,
+        offset: usize,
+    },
+    /// The entry has fewer or more values than the standard allows
+    CountOutOfRange {
+        tag: u16,
+        exif_tag: ExifTag,
+        kind: IfdKind,
+        format: IfdFormat,
+        min: u32,
+        max: u32,
+        found: u32,
+        index: usize,
+        offset: usize,
+    },
+    /// The data of the entry lies past the end of the TIFF data. The entry is left out.
+    DataBeyondEof {
+        tag: u16,
+        kind: IfdKind,
+        /// Position in the file the data was expected at
+        data_offset: usize,
+        /// Length of the data, in bytes
+        length: usize,
+        index: usize,
+        offset: usize,
+    },
+    /// The tag is not known to this crate. The entry is kept as `ExifTag::UnknownToMe`.
+    UnknownTag {
+        tag: u16,
+        kind: IfdKind,
+        format: IfdFormat,
+        index: usize,
+        offset: usize,
+    },
+    /// The IFD declares more entries than fit in the data. The entries that fit are kept.
+    IfdTruncated {
+        kind: IfdKind,
+        /// Number of entries the IFD declares
+        declared: u16,
+        /// Number of entries read
+        read: u16,
+        /// Position of the IFD
+        offset: usize,
+    },
+    /// A sub-IFD could not be parsed. Its entries are left out.
+    IfdSkipped {
+        kind: IfdKind,
+        reason: String,
+        /// Position of the IFD
+        offset: usize,
+    },
 }
 
 /// Structure that represents a parsed IFD entry of a TIFF image
@@ -342,28 +541,90 @@ pub enum Namespace {
 /// the `Namespace` enumeration. The namespace is 0 for standard Exif tags.
 /// The non-standard namespaces exist to accomodate future parsing of the
 /// MarkerNote tag, that contains embedded manufacturer-specific tags.
+///
+/// Tag codes are only unique within an IFD. Tags of the Interoperability IFD,
+/// whose codes overlap with GPS tags, have 0x0100 in the most significant word
+/// so that they stay distinct from them.
 #[derive(Copy, Clone, Debug, PartialEq, Hash)]
 pub enum ExifTag {
     /// Tag not recognized are partially parsed. The client may still try to interpret
     /// the tag by reading into the IfdFormat structure.
     UnknownToMe = 0x0000_ffff,
+    NewSubfileType = 0x0000_00fe,
+    SubfileType = 0x0000_00ff,
+    ImageWidth = 0x0000_0100,
+    ImageLength = 0x0000_0101,
+    BitsPerSample = 0x0000_0102,
+    Compression = 0x0000_0103,
+    PhotometricInterpretation = 0x0000_0106,
+    Thresholding = 0x0000_0107,
+    CellWidth = 0x0000_0108,
+    CellLength = 0x0000_0109,
+    FillOrder = 0x0000_010a,
+    DocumentName = 0x0000_010d,
     ImageDescription = 0x0000_010e,
     Make = 0x0000_010f,
     Model = 0x0000_0110,
+    StripOffsets = 0x0000_0111,
     Orientation = 0x0000_0112,
+    SamplesPerPixel = 0x0000_0115,
+    RowsPerStrip = 0x0000_0116,
+    StripByteCounts = 0x0000_0117,
+    MinSampleValue = 0x0000_0118,
+    MaxSampleValue = 0x0000_0119,
     XResolution = 0x0000_011a,
     YResolution = 0x0000_011b,
+    PlanarConfiguration = 0x0000_011c,
+    PageName = 0x0000_011d,
+    XPosition = 0x0000_011e,
+    YPosition = 0x0000_011f,
+    FreeOffsets = 0x0000_0120,
+    FreeByteCounts = 0x0000_0121,
+    GrayResponseUnit = 0x0000_0122,
+    GrayResponseCurve = 0x0000_0123,
+    T4Options = 0x0000_0124,
+    T6Options = 0x0000_0125,
     ResolutionUnit = 0x0000_0128,
+    PageNumber = 0x0000_0129,
+    TransferFunction = 0x0000_012d,
     Software = 0x0000_0131,
     DateTime = 0x0000_0132,
+    Artist = 0x0000_013b,
     HostComputer = 0x0000_013c,
+    Predictor = 0x0000_013d,
     WhitePoint = 0x0000_013e,
     PrimaryChromaticities = 0x0000_013f,
+    ColorMap = 0x0000_0140,
+    HalftoneHints = 0x0000_0141,
+    TileWidth = 0x0000_0142,
+    TileLength = 0x0000_0143,
+    TileOffsets = 0x0000_0144,
+    TileByteCounts = 0x0000_0145,
+    SubIFDs = 0x0000_014a,
+    InkSet = 0x0000_014c,
+    InkNames = 0x0000_014d,
+    NumberOfInks = 0x0000_014e,
+    DotRange = 0x0000_0150,
+    TargetPrinter = 0x0000_0151,
+    ExtraSamples = 0x0000_0152,
+    SampleFormat = 0x0000_0153,
+    TransferRange = 0x0000_0156,
+    JPEGTables = 0x0000_015b,
+    JPEGProc = 0x0000_0200,
+    JPEGInterchangeFormat = 0x0000_0201,
+    JPEGInterchangeFormatLength = 0x0000_0202,
+    JPEGRestartInterval = 0x0000_0203,
     YCbCrCoefficients = 0x0000_0211,
+    YCbCrSubSampling = 0x0000_0212,
+    YCbCrPositioning = 0x0000_0213,
     ReferenceBlackWhite = 0x0000_0214,
+    XMLPacket = 0x0000_02bc,
+    ImageID = 0x0000_800d,
     Copyright = 0x0000_8298,
+    InterColorProfile = 0x0000_8773,
     ExifOffset = 0x0000_8769,
     GPSOffset = 0x0000_8825,
+    InteropOffset = 0x0000_a005,
 
     ExposureTime = 0x0000_829a,
     FNumber = 0x0000_829d,
@@ -372,9 +633,17 @@ pub enum ExifTag {
     ISOSpeedRatings = 0x0000_8827,
     OECF = 0x0000_8828,
     SensitivityType = 0x0000_8830,
+    StandardOutputSensitivity = 0x0000_8831,
+    RecommendedExposureIndex = 0x0000_8832,
+    ISOSpeed = 0x0000_8833,
+    ISOSpeedLatitudeyyy = 0x0000_8834,
+    ISOSpeedLatitudezzz = 0x0000_8835,
     ExifVersion = 0x0000_9000,
     DateTimeOriginal = 0x0000_9003,
     DateTimeDigitized = 0x0000_9004,
+    OffsetTime = 0x0000_9010,
+    OffsetTimeOriginal = 0x0000_9011,
+    OffsetTimeDigitized = 0x0000_9012,
     ShutterSpeedValue = 0x0000_9201,
     ApertureValue = 0x0000_9202,
     BrightnessValue = 0x0000_9203,
@@ -388,6 +657,9 @@ pub enum ExifTag {
     SubjectArea = 0x0000_9214,
     MakerNote = 0x0000_927c,
     UserComment = 0x0000_9286,
+    SubSecTime = 0x0000_9290,
+    SubSecTimeOriginal = 0x0000_9291,
+    SubSecTimeDigitized = 0x0000_9292,
     FlashPixVersion = 0x0000_a000,
     ColorSpace = 0x0000_a001,
     RelatedSoundFile = 0x0000_a004,
@@ -414,11 +686,129 @@ pub enum ExifTag {
     DeviceSettingDescription = 0x0000_a40b,
     SubjectDistanceRange = 0x0000_a40c,
     ImageUniqueID = 0x0000_a420,
+    CameraOwnerName = 0x0000_a430,
+    BodySerialNumber = 0x0000_a431,
     LensSpecification = 0x0000_a432,
     LensMake = 0x0000_a433,
     LensModel = 0x0000_a434,
+    LensSerialNumber = 0x0000_a435,
     Gamma = 0xa500,
 
+    DNGVersion = 0x0000_c612,
+    DNGBackwardVersion = 0x0000_c613,
+    UniqueCameraModel = 0x0000_c614,
+    LocalizedCameraModel = 0x0000_c615,
+    CFAPlaneColor = 0x0000_c616,
+    CFALayout = 0x0000_c617,
+    LinearizationTable = 0x0000_c618,
+    BlackLevelRepeatDim = 0x0000_c619,
+    BlackLevel = 0x0000_c61a,
+    BlackLevelDeltaH = 0x0000_c61b,
+    BlackLevelDeltaV = 0x0000_c61c,
+    WhiteLevel = 0x0000_c61d,
+    DefaultScale = 0x0000_c61e,
+    DefaultCropOrigin = 0x0000_c61f,
+    DefaultCropSize = 0x0000_c620,
+    ColorMatrix1 = 0x0000_c621,
+    ColorMatrix2 = 0x0000_c622,
+    CameraCalibration1 = 0x0000_c623,
+    CameraCalibration2 = 0x0000_c624,
+    ReductionMatrix1 = 0x0000_c625,
+    ReductionMatrix2 = 0x0000_c626,
+    AnalogBalance = 0x0000_c627,
+    AsShotNeutral = 0x0000_c628,
+    AsShotWhiteXY = 0x0000_c629,
+    BaselineExposure = 0x0000_c62a,
+    BaselineNoise = 0x0000_c62b,
+    BaselineSharpness = 0x0000_c62c,
+    BayerGreenSplit = 0x0000_c62d,
+    LinearResponseLimit = 0x0000_c62e,
+    CameraSerialNumber = 0x0000_c62f,
+    LensInfo = 0x0000_c630,
+    ChromaBlurRadius = 0x0000_c631,
+    AntiAliasStrength = 0x0000_c632,
+    ShadowScale = 0x0000_c633,
+    DNGPrivateData = 0x0000_c634,
+    MakerNoteSafety = 0x0000_c635,
+    CalibrationIlluminant1 = 0x0000_c65a,
+    CalibrationIlluminant2 = 0x0000_c65b,
+    BestQualityScale = 0x0000_c65c,
+    RawDataUniqueID = 0x0000_c65d,
+    OriginalRawFileName = 0x0000_c68b,
+    OriginalRawFileData = 0x0000_c68c,
+    ActiveArea = 0x0000_c68d,
+    MaskedAreas = 0x0000_c68e,
+    AsShotICCProfile = 0x0000_c68f,
+    AsShotPreProfileMatrix = 0x0000_c690,
+    CurrentICCProfile = 0x0000_c691,
+    CurrentPreProfileMatrix = 0x0000_c692,
+    ColorimetricReference = 0x0000_c6bf,
+    CameraCalibrationSignature = 0x0000_c6f3,
+    ProfileCalibrationSignature = 0x0000_c6f4,
+    ExtraCameraProfiles = 0x0000_c6f5,
+    AsShotProfileName = 0x0000_c6f6,
+    NoiseReductionApplied = 0x0000_c6f7,
+    ProfileName = 0x0000_c6f8,
+    ProfileHueSatMapDims = 0x0000_c6f9,
+    ProfileHueSatMapData1 = 0x0000_c6fa,
+    ProfileHueSatMapData2 = 0x0000_c6fb,
+    ProfileToneCurve = 0x0000_c6fc,
+    ProfileEmbedPolicy = 0x0000_c6fd,
+    ProfileCopyright = 0x0000_c6fe,
+    ForwardMatrix1 = 0x0000_c714,
+    ForwardMatrix2 = 0x0000_c715,
+    PreviewApplicationName = 0x0000_c716,
+    PreviewApplicationVersion = 0x0000_c717,
+    PreviewSettingsName = 0x0000_c718,
+    PreviewSettingsDigest = 0x0000_c719,
+    PreviewColorSpace = 0x0000_c71a,
+    PreviewDateTime = 0x0000_c71b,
+    RawImageDigest = 0x0000_c71c,
+    OriginalRawFileDigest = 0x0000_c71d,
+    SubTileBlockSize = 0x0000_c71e,
+    RowInterleaveFactor = 0x0000_c71f,
+    ProfileLookTableDims = 0x0000_c725,
+    ProfileLookTableData = 0x0000_c726,
+    OpcodeList1 = 0x0000_c740,
+    OpcodeList2 = 0x0000_c741,
+    OpcodeList3 = 0x0000_c74e,
+    NoiseProfile = 0x0000_c761,
+    TimeCodes = 0x0000_c763,
+    FrameRate = 0x0000_c764,
+    TStop = 0x0000_c772,
+    ReelName = 0x0000_c789,
+    OriginalDefaultFinalSize = 0x0000_c791,
+    OriginalBestQualityFinalSize = 0x0000_c792,
+    OriginalDefaultCropSize = 0x0000_c793,
+    CameraLabel = 0x0000_c7a1,
+    ProfileHueSatMapEncoding = 0x0000_c7a3,
+    ProfileLookTableEncoding = 0x0000_c7a4,
+    BaselineExposureOffset = 0x0000_c7a5,
+    DefaultBlackRender = 0x0000_c7a6,
+    NewRawImageDigest = 0x0000_c7a7,
+    RawToPreviewGain = 0x0000_c7a8,
+    DefaultUserCrop = 0x0000_c7b5,
+    DepthFormat = 0x0000_c7d5,
+    DepthNear = 0x0000_c7d6,
+    DepthFar = 0x0000_c7d7,
+    DepthUnits = 0x0000_c7d8,
+    DepthMeasureType = 0x0000_c7d9,
+    EnhanceParams = 0x0000_c7da,
+    ProfileGainTableMap = 0x0000_cd2d,
+    SemanticName = 0x0000_cd2e,
+    SemanticInstanceID = 0x0000_cd30,
+    CalibrationIlluminant3 = 0x0000_cd31,
+    CameraCalibration3 = 0x0000_cd32,
+    ColorMatrix3 = 0x0000_cd33,
+    ForwardMatrix3 = 0x0000_cd34,
+    IlluminantData1 = 0x0000_cd35,
+    IlluminantData2 = 0x0000_cd36,
+    IlluminantData3 = 0x0000_cd37,
+    MaskSubArea = 0x0000_cd38,
+    ProfileHueSatMapData3 = 0x0000_cd39,
+    ReductionMatrix3 = 0x0000_cd3a,
+    RGBTables = 0x0000_cd3b,
+
     GPSVersionID = 0x00000,
     GPSLatitudeRef = 0x00001,
     GPSLatitude = 0x00002,
@@ -450,6 +840,12 @@ pub enum ExifTag {
     GPSAreaInformation = 0x0000_001c,
     GPSDateStamp = 0x0000_001d,
     GPSDifferential = 0x0000_001e,
+
+    InteroperabilityIndex = 0x0100_0001,
+    InteroperabilityVersion = 0x0100_0002,
+    RelatedImageFileFormat = 0x0100_1000,
+    RelatedImageWidth = 0x0100_1001,
+    RelatedImageLength = 0x0100_1002,
 }
 
 impl Eq for ExifTag {}
@@ -460,25 +856,88 @@ impl fmt::Display for ExifTag {
             f,
             "{}",
             match *self {
+                ExifTag::NewSubfileType => "New subfile type",
+                ExifTag::SubfileType => "Subfile type",
+                ExifTag::ImageWidth => "Image width",
+                ExifTag::ImageLength => "Image length",
+                ExifTag::BitsPerSample => "Bits per sample",
+                ExifTag::Compression => "Compression",
+                ExifTag::PhotometricInterpretation => "Photometric interpretation",
+                ExifTag::Thresholding => "Thresholding",
+                ExifTag::CellWidth => "Cell width",
+                ExifTag::CellLength => "Cell length",
+                ExifTag::FillOrder => "Fill order",
+                ExifTag::DocumentName => "Document name",
                 ExifTag::ImageDescription => "Image Description",
                 ExifTag::Make => "Manufacturer",
                 ExifTag::HostComputer => "Host computer",
                 ExifTag::Model => "Model",
+                ExifTag::StripOffsets => "Strip offsets",
                 ExifTag::Orientation => "Orientation",
+                ExifTag::SamplesPerPixel => "Samples per pixel",
+                ExifTag::RowsPerStrip => "Rows per strip",
+                ExifTag::StripByteCounts => "Strip byte counts",
+                ExifTag::MinSampleValue => "Minimum sample value",
+                ExifTag::MaxSampleValue => "Maximum sample value",
                 ExifTag::XResolution => "X Resolution",
                 ExifTag::YResolution => "Y Resolution",
+                ExifTag::PlanarConfiguration => "Planar configuration",
+                ExifTag::PageName => "Page name",
+                ExifTag::XPosition => "X position",
+                ExifTag::YPosition => "Y position",
+                ExifTag::FreeOffsets => "Free offsets",
+                ExifTag::FreeByteCounts => "Free byte counts",
+                ExifTag::GrayResponseUnit => "Gray response unit",
+                ExifTag::GrayResponseCurve => "Gray response curve",
+                ExifTag::T4Options => "T4 options",
+                ExifTag::T6Options => "T6 options",
                 ExifTag::ResolutionUnit => "Resolution Unit",
+                ExifTag::PageNumber => "Page number",
+                ExifTag::TransferFunction => "Transfer function",
                 ExifTag::Software => "Software",
                 ExifTag::DateTime => "Image date",
+                ExifTag::Artist => "Artist",
+                ExifTag::Predictor => "Predictor",
                 ExifTag::WhitePoint => "White Point",
                 ExifTag::PrimaryChromaticities => "Primary Chromaticities",
+                ExifTag::ColorMap => "Color map",
+                ExifTag::HalftoneHints => "Halftone hints",
+                ExifTag::TileWidth => "Tile width",
+                ExifTag::TileLength => "Tile length",
+                ExifTag::TileOffsets => "Tile offsets",
+                ExifTag::TileByteCounts => "Tile byte counts",
+                ExifTag::SubIFDs => "Sub-IFD offsets",
+                ExifTag::InkSet => "Ink set",
+                ExifTag::InkNames => "Ink names",
+                ExifTag::NumberOfInks => "Number of inks",
+                ExifTag::DotRange => "Dot range",
+                ExifTag::TargetPrinter => "Target printer",
+                ExifTag::ExtraSamples => "Extra samples",
+                ExifTag::SampleFormat => "Sample format",
+                ExifTag::TransferRange => "Transfer range",
+                ExifTag::JPEGTables => "JPEG tables",
+                ExifTag::JPEGProc => "JPEG process",
+                ExifTag::JPEGInterchangeFormat => "JPEG interchange format offset",
+                ExifTag::JPEGInterchangeFormatLength => "JPEG interchange format length",
+                ExifTag::JPEGRestartInterval => "JPEG restart interval",
                 ExifTag::YCbCrCoefficients => "YCbCr Coefficients",
+                ExifTag::YCbCrSubSampling => "YCbCr subsampling",
+                ExifTag::YCbCrPositioning => "YCbCr positioning",
                 ExifTag::ReferenceBlackWhite => "Reference Black/White",
+                ExifTag::XMLPacket => "XMP packet",
+                ExifTag::ImageID => "Image ID",
                 ExifTag::Copyright => "Copyright",
+                ExifTag::InterColorProfile => "ICC profile",
                 ExifTag::ExifOffset => "This image has an Exif SubIFD",
                 ExifTag::GPSOffset => "This image has a GPS SubIFD",
+                ExifTag::InteropOffset => "This image has an Interoperability SubIFD",
                 ExifTag::ExposureTime => "Exposure time",
                 ExifTag::SensitivityType => "Sensitivity type",
+                ExifTag::StandardOutputSensitivity => "Standard output sensitivity",
+                ExifTag::RecommendedExposureIndex => "Recommended exposure index",
+                ExifTag::ISOSpeed => "ISO speed",
+                ExifTag::ISOSpeedLatitudeyyy => "ISO speed latitude yyy",
+                ExifTag::ISOSpeedLatitudezzz => "ISO speed latitude zzz",
                 ExifTag::FNumber => "Aperture",
                 ExifTag::ExposureProgram => "Exposure program",
                 ExifTag::SpectralSensitivity => "Spectral sensitivity",
@@ -487,6 +946,9 @@ impl fmt::Display for ExifTag {
                 ExifTag::ExifVersion => "Exif version",
                 ExifTag::DateTimeOriginal => "Date of original image",
                 ExifTag::DateTimeDigitized => "Date of image digitalization",
+                ExifTag::OffsetTime => "Image date UTC offset",
+                ExifTag::OffsetTimeOriginal => "Date of original image UTC offset",
+                ExifTag::OffsetTimeDigitized => "Date of image digitalization UTC offset",
                 ExifTag::ShutterSpeedValue => "Shutter speed",
                 ExifTag::ApertureValue => "Aperture value",
                 ExifTag::BrightnessValue => "Brightness value",
@@ -500,6 +962,9 @@ impl fmt::Display for ExifTag {
                 ExifTag::SubjectArea => "Subject area",
                 ExifTag::MakerNote => "Maker note",
                 ExifTag::UserComment => "User comment",
+                ExifTag::SubSecTime => "Image date sub-seconds",
+                ExifTag::SubSecTimeOriginal => "Date of original image sub-seconds",
+                ExifTag::SubSecTimeDigitized => "Date of image digitalization sub-seconds",
                 ExifTag::FlashPixVersion => "Flashpix version",
                 ExifTag::ColorSpace => "Color space",
                 ExifTag::FlashEnergy => "Flash energy",
@@ -523,10 +988,127 @@ impl fmt::Display for ExifTag {
                 ExifTag::Contrast => "Contrast",
                 ExifTag::Saturation => "Saturation",
                 ExifTag::Sharpness => "Sharpness",
+                ExifTag::CameraOwnerName => "Camera owner",
+                ExifTag::BodySerialNumber => "Camera serial number",
                 ExifTag::LensSpecification => "Lens specification",
                 ExifTag::LensMake => "Lens manufacturer",
                 ExifTag::LensModel => "Lens model",
+                ExifTag::LensSerialNumber => "Lens serial number",
                 ExifTag::Gamma => "Gamma",
+                ExifTag::DNGVersion => "DNG version",
+                ExifTag::DNGBackwardVersion => "DNG backward version",
+                ExifTag::UniqueCameraModel => "Unique camera model",
+                ExifTag::LocalizedCameraModel => "Localized camera model",
+                ExifTag::CFAPlaneColor => "CFA plane color",
+                ExifTag::CFALayout => "CFA layout",
+                ExifTag::LinearizationTable => "Linearization table",
+                ExifTag::BlackLevelRepeatDim => "Black level repeat dimensions",
+                ExifTag::BlackLevel => "Black level",
+                ExifTag::BlackLevelDeltaH => "Black level delta H",
+                ExifTag::BlackLevelDeltaV => "Black level delta V",
+                ExifTag::WhiteLevel => "White level",
+                ExifTag::DefaultScale => "Default scale",
+                ExifTag::DefaultCropOrigin => "Default crop origin",
+                ExifTag::DefaultCropSize => "Default crop size",
+                ExifTag::ColorMatrix1 => "Color matrix 1",
+                ExifTag::ColorMatrix2 => "Color matrix 2",
+                ExifTag::CameraCalibration1 => "Camera calibration 1",
+                ExifTag::CameraCalibration2 => "Camera calibration 2",
+                ExifTag::ReductionMatrix1 => "Reduction matrix 1",
+                ExifTag::ReductionMatrix2 => "Reduction matrix 2",
+                ExifTag::AnalogBalance => "Analog balance",
+                ExifTag::AsShotNeutral => "As shot neutral",
+                ExifTag::AsShotWhiteXY => "As shot white XY",
+                ExifTag::BaselineExposure => "Baseline exposure",
+                ExifTag::BaselineNoise => "Baseline noise",
+                ExifTag::BaselineSharpness => "Baseline sharpness",
+                ExifTag::BayerGreenSplit => "Bayer green split",
+                ExifTag::LinearResponseLimit => "Linear response limit",
+                ExifTag::CameraSerialNumber => "Camera serial number",
+                ExifTag::LensInfo => "Lens information",
+                ExifTag::ChromaBlurRadius => "Chroma blur radius",
+                ExifTag::AntiAliasStrength => "Anti-alias strength",
+                ExifTag::ShadowScale => "Shadow scale",
+                ExifTag::DNGPrivateData => "DNG private data",
+                ExifTag::MakerNoteSafety => "Maker note safety",
+                ExifTag::CalibrationIlluminant1 => "Calibration illuminant 1",
+                ExifTag::CalibrationIlluminant2 => "Calibration illuminant 2",
+                ExifTag::BestQualityScale => "Best quality scale",
+                ExifTag::RawDataUniqueID => "Raw data unique ID",
+                ExifTag::OriginalRawFileName => "Original raw file name",
+                ExifTag::OriginalRawFileData => "Original raw file data",
+                ExifTag::ActiveArea => "Active area",
+                ExifTag::MaskedAreas => "Masked areas",
+                ExifTag::AsShotICCProfile => "As shot ICC profile",
+                ExifTag::AsShotPreProfileMatrix => "As shot pre-profile matrix",
+                ExifTag::CurrentICCProfile => "Current ICC profile",
+                ExifTag::CurrentPreProfileMatrix => "Current pre-profile matrix",
+                ExifTag::ColorimetricReference => "Colorimetric reference",
+                ExifTag::CameraCalibrationSignature => "Camera calibration signature",
+                ExifTag::ProfileCalibrationSignature => "Profile calibration signature",
+                ExifTag::ExtraCameraProfiles => "Extra camera profiles",
+                ExifTag::AsShotProfileName => "As shot profile name",
+                ExifTag::NoiseReductionApplied => "Noise reduction applied",
+                ExifTag::ProfileName => "Profile name",
+                ExifTag::ProfileHueSatMapDims => "Profile hue/saturation map dimensions",
+                ExifTag::ProfileHueSatMapData1 => "Profile hue/saturation map data 1",
+                ExifTag::ProfileHueSatMapData2 => "Profile hue/saturation map data 2",
+                ExifTag::ProfileToneCurve => "Profile tone curve",
+                ExifTag::ProfileEmbedPolicy => "Profile embed policy",
+                ExifTag::ProfileCopyright => "Profile copyright",
+                ExifTag::ForwardMatrix1 => "Forward matrix 1",
+                ExifTag::ForwardMatrix2 => "Forward matrix 2",
+                ExifTag::PreviewApplicationName => "Preview application name",
+                ExifTag::PreviewApplicationVersion => "Preview application version",
+                ExifTag::PreviewSettingsName => "Preview settings name",
+                ExifTag::PreviewSettingsDigest => "Preview settings digest",
+                ExifTag::PreviewColorSpace => "Preview color space",
+                ExifTag::PreviewDateTime => "Preview date",
+                ExifTag::RawImageDigest => "Raw image digest",
+                ExifTag::OriginalRawFileDigest => "Original raw file digest",
+                ExifTag::SubTileBlockSize => "Sub-tile block size",
+                ExifTag::RowInterleaveFactor => "Row interleave factor",
+                ExifTag::ProfileLookTableDims => "Profile look table dimensions",
+                ExifTag::ProfileLookTableData => "Profile look table data",
+                ExifTag::OpcodeList1 => "Opcode list 1",
+                ExifTag::OpcodeList2 => "Opcode list 2",
+                ExifTag::OpcodeList3 => "Opcode list 3",
+                ExifTag::NoiseProfile => "Noise profile",
+                ExifTag::TimeCodes => "Time codes",
+                ExifTag::FrameRate => "Frame rate",
+                ExifTag::TStop => "T-stop",
+                ExifTag::ReelName => "Reel name",
+                ExifTag::OriginalDefaultFinalSize => "Original default final size",
+                ExifTag::OriginalBestQualityFinalSize => "Original best quality final size",
+                ExifTag::OriginalDefaultCropSize => "Original default crop size",
+                ExifTag::CameraLabel => "Camera label",
+                ExifTag::ProfileHueSatMapEncoding => "Profile hue/saturation map encoding",
+                ExifTag::ProfileLookTableEncoding => "Profile look table encoding",
+                ExifTag::BaselineExposureOffset => "Baseline exposure offset",
+                ExifTag::DefaultBlackRender => "Default black render",
+                ExifTag::NewRawImageDigest => "New raw image digest",
+                ExifTag::RawToPreviewGain => "Raw to preview gain",
+                ExifTag::DefaultUserCrop => "Default user crop",
+                ExifTag::DepthFormat => "Depth format",
+                ExifTag::DepthNear => "Depth near",
+                ExifTag::DepthFar => "Depth far",
+                ExifTag::DepthUnits => "Depth units",
+                ExifTag::DepthMeasureType => "Depth measure type",
+                ExifTag::EnhanceParams => "Enhance parameters",
+                ExifTag::ProfileGainTableMap => "Profile gain table map",
+                ExifTag::SemanticName => "Semantic name",
+                ExifTag::SemanticInstanceID => "Semantic instance ID",
+                ExifTag::CalibrationIlluminant3 => "Calibration illuminant 3",
+                ExifTag::CameraCalibration3 => "Camera calibration 3",
+                ExifTag::ColorMatrix3 => "Color matrix 3",
+                ExifTag::ForwardMatrix3 => "Forward matrix 3",
+                ExifTag::IlluminantData1 => "Illuminant data 1",
+                ExifTag::IlluminantData2 => "Illuminant data 2",
+                ExifTag::IlluminantData3 => "Illuminant data 3",
+                ExifTag::MaskSubArea => "Mask sub-area",
+                ExifTag::ProfileHueSatMapData3 => "Profile hue/saturation map data 3",
+                ExifTag::ReductionMatrix3 => "Reduction matrix 3",
+                ExifTag::RGBTables => "RGB tables",
                 ExifTag::DeviceSettingDescription => "Device setting description",
                 ExifTag::SubjectDistanceRange => "Subject distance range",
                 ExifTag::ImageUniqueID => "Image unique ID",
@@ -561,6 +1143,11 @@ impl fmt::Display for ExifTag {
                 ExifTag::GPSAreaInformation => "GPS area information",
                 ExifTag::GPSDateStamp => "GPS date stamp",
                 ExifTag::GPSDifferential => "GPS differential",
+                ExifTag::InteroperabilityIndex => "Interoperability index",
+                ExifTag::InteroperabilityVersion => "Interoperability version",
+                ExifTag::RelatedImageFileFormat => "Related image file format",
+                ExifTag::RelatedImageWidth => "Related image width",
+                ExifTag::RelatedImageLength => "Related image length",
                 ExifTag::UnknownToMe => "Unknown to this library, or manufacturer-specific",
             }
         )
@@ -586,6 +1173,8 @@ pub enum IfdFormat {
     IRational = 10,
     F32 = 11,
     F64 = 12,
+    /// UTF-8 string, introduced by Exif 3.0
+    Utf8 = 129,
 }
 
 /// Structure that represents a parsed EXIF tag.
@@ -624,10 +1213,10 @@ pub struct ExifEntry {
 
 impl PartialEq for ExifEntry {
     fn eq(&self, other: &ExifEntry) -> bool {
-        // If the ExifEntry is an ExifOffset or a GPSOffset, the value it contains is an offset.
-        // Two entries can be equal even if they do not point to the same offset.
+        // If the ExifEntry is an ExifOffset, GPSOffset or InteropOffset, the value it contains
+        // is an offset. Two entries can be equal even if they do not point to the same offset.
         let value_eq = match self.tag {
-            ExifTag::ExifOffset | ExifTag::GPSOffset => true,
+            ExifTag::ExifOffset | ExifTag::GPSOffset | ExifTag::InteropOffset => true,
             _ => {
                 self.value_more_readable == other.value_more_readable && tag_value_eq(&self.value, &other.value)
             },
@@ -646,6 +1235,7 @@ pub enum TagValue {
     /// Array of unsigned byte integers
     U8(Vec<u8>),
     /// ASCII string. (The standard specifies 7-bit ASCII, but this parser accepts UTF-8 strings.)
+    /// Values of the Exif 3.0 UTF-8 format are decoded into this variant as well.
     Ascii(String),
     U16(Vec<u16>),
     U32(Vec<u32>),
@@ -712,12 +1302,41 @@ impl TagValue {
             TagValue::I16(ref v) => v.get(index).cloned().map(From::from),
             TagValue::I32(ref v) => v.get(index).cloned().map(From::from),
             TagValue::F32(ref v) => v.get(index).cloned().map(From::from),
-            TagValue::F64(ref v) => v.get(index).cloned().map(From::from),
+            TagValue::F64(ref v) => v.get(index).cloned(),
             TagValue::IRational(ref v) => v.get(index).cloned().map(|v| v.value()),
             TagValue::URational(ref v) => v.get(index).cloned().map(|v| v.value()),
             _ => None,
         }
     }
+
+    /// Get value as an unsigned integer, whether it is stored as BYTE, SHORT or LONG
+    /// Out of bounds indexes and invalid types return `None`
+    pub fn to_u32(&self, index: usize) -> Option<u32> {
+        match *self {
+            TagValue::U8(ref v) => v.get(index).cloned().map(From::from),
+            TagValue::U16(ref v) => v.get(index).cloned().map(From::from),
+            TagValue::U32(ref v) => v.get(index).cloned(),
+            _ => None,
+        }
+    }
+
+    /// Get value as an unsigned rational. Integers are returned with a denominator of 1.
+    /// Out of bounds indexes and invalid types return `None`
+    pub fn to_urational(&self, index: usize) -> Option<URational> {
+        match *self {
+            TagValue::URational(ref v) => v.get(index).cloned(),
+            _ => self.to_u32(index).map(|numerator| URational { numerator, denominator: 1 }),
+        }
+    }
+
+    /// Get value as a string slice
+    /// Types other than Ascii return `None`
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            TagValue::Ascii(ref s) => Some(s),
+            _ => None,
+        }
+    }
 }
 
 /// Type returned by image file parsing
@@ -726,7 +1345,7 @@ pub type ExifResult = Result<ExifData, ExifError>;
 /// Type resturned by lower-level parsing functions
 pub type ExifEntryResult = Result<Vec<ExifEntry>, ExifError>;
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum IfdKind {
     Ifd0,
     Ifd1,
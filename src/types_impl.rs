@@ -1,5 +1,6 @@
 use crate::ifdformat::NumArray;
 use super::lowlevel::*;
+use super::rational::*;
 use super::types::*;
 use std::error::Error;
 use std::fmt;
@@ -28,12 +29,56 @@ impl IfdFormat {
             10 => IfdFormat::IRational,
             11 => IfdFormat::F32,
             12 => IfdFormat::F64,
+            129 => IfdFormat::Utf8,
             _ => IfdFormat::Unknown,
         }
     }
+
+    /// Size of one value of this format, in bytes
+    pub(crate) fn size(self) -> u8 {
+        match self {
+            IfdFormat::U8 => 1,
+            IfdFormat::Ascii => 1,
+            IfdFormat::U16 => 2,
+            IfdFormat::U32 => 4,
+            IfdFormat::URational => 8,
+            IfdFormat::I8 => 1,
+            IfdFormat::Undefined => 1,
+            IfdFormat::I16 => 2,
+            IfdFormat::I32 => 4,
+            IfdFormat::IRational => 8,
+            IfdFormat::F32 => 4,
+            IfdFormat::F64 => 8,
+            IfdFormat::Utf8 => 1,
+            IfdFormat::Unknown => 1,
+        }
+    }
 }
 
 impl IfdEntry {
+    /// Entry of the standard namespace with `count` values of `format`, encoded
+    /// in `data` with the byte order given by `le`
+    pub(crate) fn new(tag: u16, format: IfdFormat, count: u32, mut data: Vec<u8>, le: bool) -> IfdEntry {
+        // Like the parser, keep the 4 bytes of the IFD entry as data when it fits
+        let (ifd_data, ext_data) = if data.len() <= 4 {
+            data.resize(4, 0);
+            (data.clone(), Vec::new())
+        } else {
+            // The offset is only known once serialized
+            (vec![0; 4], data.clone())
+        };
+        IfdEntry {
+            namespace: Namespace::Standard,
+            tag,
+            format,
+            count,
+            data,
+            ifd_data,
+            ext_data,
+            le,
+        }
+    }
+
     #[deprecated]
     pub fn data_as_offset(&self) -> usize {
         self.try_data_as_offset().unwrap()
@@ -52,27 +97,13 @@ impl IfdEntry {
     /// IFD entry contains an array of elements, so this is NOT the size of the
     /// whole entry!
     pub fn size(&self) -> u8 {
-        match self.format {
-            IfdFormat::U8 => 1,
-            IfdFormat::Ascii => 1,
-            IfdFormat::U16 => 2,
-            IfdFormat::U32 => 4,
-            IfdFormat::URational => 8,
-            IfdFormat::I8 => 1,
-            IfdFormat::Undefined => 1,
-            IfdFormat::I16 => 2,
-            IfdFormat::I32 => 4,
-            IfdFormat::IRational => 8,
-            IfdFormat::F32 => 4,
-            IfdFormat::F64 => 8,
-            IfdFormat::Unknown => 1,
-        }
+        self.format.size()
     }
 
     /// Total length of the whole IFD entry (element count x element size)
     #[inline]
     pub fn length(&self) -> usize {
-        (self.size() as usize) * (self.count as usize)
+        (self.size() as usize).saturating_mul(self.count as usize)
     }
 
     /// Returns true if data is contained within the IFD structure, false when
@@ -98,7 +129,7 @@ impl IfdEntry {
             Some(o) => o,
             _ => return false,
         };
-        if let Some(ext_data) = contents.get(offset..(offset + self.length())) {
+        if let Some(ext_data) = contents.get(offset..offset.saturating_add(self.length())) {
             self.ext_data.clear();
             self.ext_data.extend(ext_data);
             self.data = self.ext_data.clone();
@@ -120,11 +151,143 @@ impl Display for ExifError {
             ExifError::JpegWithoutExif(ref s) => write!(f, "JPEG without EXIF section: {}", s),
             ExifError::TiffTruncated => f.write_str("TIFF truncated at start"),
             ExifError::TiffBadPreamble(ref s) => write!(f, "TIFF with bad preamble: {}", s),
-            ExifError::IfdTruncated => f.write_str("TIFF IFD truncated"),
-            ExifError::ExifIfdTruncated(ref s) => write!(f, "TIFF Exif IFD truncated: {}", s),
+            ExifError::IfdTruncated { kind, offset } => write!(f, "TIFF IFD truncated ({:?} IFD at offset {})", kind, offset),
+            ExifError::ExifIfdTruncated { kind, offset, ref reason } => {
+                write!(f, "TIFF Exif IFD truncated: {} ({:?} IFD at offset {})", reason, kind, offset)
+            },
             ExifError::ExifIfdEntryNotFound => f.write_str("TIFF Exif IFD not found"),
             ExifError::UnsupportedNamespace => f.write_str("Only standar namespace can be serialized"),
             ExifError::MissingExifOffset => f.write_str("Expected to have seen ExifOffset tagin IFD0"),
+            ExifError::NonConforming(ref w) => write!(f, "Non-conforming image: {}", w),
+            ExifError::TooManyEntries { limit } => write!(f, "More than {} IFD entries", limit),
+            ExifError::ValueTooLarge { kind, tag, length, limit, .. } => write!(f,
+                "EXIF tag {:x} {} in {:?} IFD has {} bytes of data, more than {}", tag, tag, kind, length, limit),
+            ExifError::TooMuchData { limit } => write!(f, "More than {} bytes of EXIF data", limit),
+            ExifError::IfdTooDeep { kind, offset, limit } => write!(f,
+                "{:?} IFD at offset {} is nested more than {} deep", kind, offset, limit),
+            ExifError::TooManyIfds { limit } => write!(f, "More than {} IFDs", limit),
+            ExifError::TagNotInIfd { tag, kind } => write!(f, "Tag {:?} does not belong in the {:?} IFD", tag, kind),
+            ExifError::WrongTagFormat { tag, expected, found } => write!(f,
+                "Tag {:?} expects format {:?}, found {:?}", tag, expected, found),
+            ExifError::WrongTagCount { tag, min, max, found } => write!(f,
+                "Tag {:?} expects count {}..{}, found {}", tag, min, max, found),
+            ExifError::InvalidTagValue { tag, ref reason } => write!(f, "Invalid value for tag {:?}: {}", tag, reason),
+            ExifError::InvalidJpeg(ref s) => write!(f, "Invalid JPEG: {}", s),
+            ExifError::JpegSegmentTooLarge { size } => write!(f,
+                "EXIF data of {} bytes does not fit in a JPEG segment", size),
+            ExifError::ByteOrderMismatch => write!(f, "EXIF data and TIFF file have different byte orders"),
+            ExifError::TiffTooLarge { size } => write!(f, "TIFF file of {} bytes is larger than 4 GiB", size),
+            ExifError::InvalidPng(ref s) => write!(f, "Invalid PNG: {}", s),
+            ExifError::InvalidWebp(ref s) => write!(f, "Invalid WebP: {}", s),
+            ExifError::UnknownFormat { kind, tag } => write!(f,
+                "Entry {:#06x} of the {:?} IFD has an unknown format or an invalid value, and cannot change byte order", tag, kind),
+        }
+    }
+}
+
+impl ExifError {
+    /// Position in the file the error refers to, if it is about a specific place
+    pub fn offset(&self) -> Option<usize> {
+        match *self {
+            ExifError::IfdTruncated { offset, .. } |
+            ExifError::ExifIfdTruncated { offset, .. } |
+            ExifError::ValueTooLarge { offset, .. } |
+            ExifError::IfdTooDeep { offset, .. } => Some(offset),
+            ExifError::NonConforming(ref w) => Some(w.offset()),
+            _ => None,
+        }
+    }
+
+    /// Turns offsets within the TIFF data into offsets within the file
+    pub(crate) fn relocate(&mut self, tiff_offset: usize) {
+        match *self {
+            ExifError::IfdTruncated { ref mut offset, .. } |
+            ExifError::ExifIfdTruncated { ref mut offset, .. } |
+            ExifError::ValueTooLarge { ref mut offset, .. } |
+            ExifError::IfdTooDeep { ref mut offset, .. } => *offset += tiff_offset,
+            ExifError::NonConforming(ref mut w) => w.relocate(tiff_offset),
+            _ => {},
+        }
+    }
+}
+
+impl ParseWarning {
+    /// Position in the file of the IFD entry, or of the IFD, concerned
+    pub fn offset(&self) -> usize {
+        match *self {
+            ParseWarning::WrongFormat { offset, .. } |
+            ParseWarning::CountOutOfRange { offset, .. } |
+            ParseWarning::DataBeyondEof { offset, .. } |
+            ParseWarning::UnknownTag { offset, .. } |
+            ParseWarning::IfdTruncated { offset, .. } |
+            ParseWarning::IfdSkipped { offset, .. } => offset,
+        }
+    }
+
+    /// IFD the problem was found in
+    pub fn ifd_kind(&self) -> IfdKind {
+        match *self {
+            ParseWarning::WrongFormat { kind, .. } |
+            ParseWarning::CountOutOfRange { kind, .. } |
+            ParseWarning::DataBeyondEof { kind, .. } |
+            ParseWarning::UnknownTag { kind, .. } |
+            ParseWarning::IfdTruncated { kind, .. } |
+            ParseWarning::IfdSkipped { kind, .. } => kind,
+        }
+    }
+
+    /// Position of the entry in its IFD, unless the warning is about a whole IFD
+    pub fn entry_index(&self) -> Option<usize> {
+        match *self {
+            ParseWarning::WrongFormat { index, .. } |
+            ParseWarning::CountOutOfRange { index, .. } |
+            ParseWarning::DataBeyondEof { index, .. } |
+            ParseWarning::UnknownTag { index, .. } => Some(index),
+            ParseWarning::IfdTruncated { .. } |
+            ParseWarning::IfdSkipped { .. } => None,
+        }
+    }
+
+    /// Whether the warning is about data that breaks the standard. Unknown tags
+    /// are allowed, since vendors and later versions of the standard add their own.
+    pub fn is_non_conforming(&self) -> bool {
+        !matches!(self, ParseWarning::UnknownTag { .. })
+    }
+
+    /// Turns offsets within the TIFF data into offsets within the file
+    pub(crate) fn relocate(&mut self, tiff_offset: usize) {
+        match *self {
+            ParseWarning::DataBeyondEof { ref mut data_offset, ref mut offset, .. } => {
+                *data_offset += tiff_offset;
+                *offset += tiff_offset;
+            },
+            ParseWarning::WrongFormat { ref mut offset, .. } |
+            ParseWarning::CountOutOfRange { ref mut offset, .. } |
+            ParseWarning::UnknownTag { ref mut offset, .. } |
+            ParseWarning::IfdTruncated { ref mut offset, .. } |
+            ParseWarning::IfdSkipped { ref mut offset, .. } => *offset += tiff_offset,
+        }
+    }
+}
+
+impl Display for ParseWarning {
+    #[cold]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ParseWarning::WrongFormat { tag, exif_tag, expected, found, .. } => write!(f,
+                "EXIF tag {:x} {} ({}), expected format {} ({:?}), found {} ({:?})",
+                tag, tag, exif_tag, expected as u8, expected, found as u8, found),
+            ParseWarning::CountOutOfRange { tag, exif_tag, format, min, max, found, .. } => write!(f,
+                "EXIF tag {:x} {} ({:?}), format {}, expected count {}..{} found {}",
+                tag, tag, exif_tag, format as u8, min, max, found),
+            ParseWarning::DataBeyondEof { tag, kind, data_offset, length, .. } => write!(f,
+                "EXIF tag {:x} {} in {:?} IFD, data at {}..{} is beyond end of data",
+                tag, tag, kind, data_offset, data_offset + length),
+            ParseWarning::UnknownTag { tag, kind, .. } => write!(f,
+                "EXIF tag {:x} {} in {:?} IFD is unknown", tag, tag, kind),
+            ParseWarning::IfdTruncated { kind, declared, read, .. } => write!(f,
+                "{:?} IFD truncated, read {} of {} entries", kind, read, declared),
+            ParseWarning::IfdSkipped { kind, ref reason, .. } => write!(f, "{:?} IFD skipped: {}", kind, reason),
         }
     }
 }
@@ -157,3 +320,42 @@ impl fmt::Display for TagValue {
         }
     }
 }
+
+macro_rules! tag_value_from {
+    ($variant:ident, $t:ty) => {
+        impl From<$t> for TagValue {
+            fn from(v: $t) -> TagValue {
+                TagValue::$variant(vec![v])
+            }
+        }
+
+        impl From<Vec<$t>> for TagValue {
+            fn from(v: Vec<$t>) -> TagValue {
+                TagValue::$variant(v)
+            }
+        }
+    };
+}
+
+tag_value_from!(U8, u8);
+tag_value_from!(U16, u16);
+tag_value_from!(U32, u32);
+tag_value_from!(URational, URational);
+tag_value_from!(I8, i8);
+tag_value_from!(I16, i16);
+tag_value_from!(I32, i32);
+tag_value_from!(IRational, IRational);
+tag_value_from!(F32, f32);
+tag_value_from!(F64, f64);
+
+impl From<&str> for TagValue {
+    fn from(s: &str) -> TagValue {
+        TagValue::Ascii(s.to_string())
+    }
+}
+
+impl From<String> for TagValue {
+    fn from(s: String) -> TagValue {
+        TagValue::Ascii(s)
+    }
+}
@@ -0,0 +1,447 @@
+use std::convert::TryFrom;
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+use super::types::*;
+
+const APP0: u8 = 0xe0;
+const APP1: u8 = 0xe1;
+const SOS: u8 = 0xda;
+const EOI: u8 = 0xd9;
+
+/// Largest payload of a JPEG segment, whose length field counts itself
+const MAX_SEGMENT_PAYLOAD: usize = 65533;
+
+/// Copy of a JPEG image with its EXIF data replaced by `exif`.
+///
+/// See `write_jpeg_to()`.
+pub fn write_jpeg(original: &[u8], exif: &ExifData) -> Result<Vec<u8>, ExifError> {
+    let mut output = Vec::with_capacity(original.len() + MAX_SEGMENT_PAYLOAD);
+    write_jpeg_to(original, exif, &mut output)?;
+    Ok(output)
+}
+
+/// Copies a JPEG image from `original` to `output`, with its EXIF data replaced
+/// by `exif`. The image is read one segment at a time, and the compressed image
+/// data is streamed.
+///
+/// The Exif APP1 segment is written right after the start of the image, or after
+/// the JFIF APP0 segments. Existing Exif APP1 segments are dropped; all other
+/// segments are copied untouched.
+pub fn write_jpeg_to<R: Read, W: Write>(mut original: R, exif: &ExifData, mut output: W) -> Result<(), ExifError> {
+    let payload = [EXIF_HEADER, &exif.serialize_tiff()?].concat();
+    if payload.len() > MAX_SEGMENT_PAYLOAD {
+        return Err(ExifError::JpegSegmentTooLarge { size: payload.len() });
+    }
+
+    let mut soi = [0; 2];
+    original.read_exact(&mut soi)?;
+    if soi != [0xff, 0xd8] {
+        return Err(ExifError::InvalidJpeg("Missing start of image".to_string()));
+    }
+    output.write_all(&soi)?;
+
+    let mut inserted = false;
+    loop {
+        let marker = read_marker(&mut original)?;
+        if !inserted && marker != APP0 {
+            write_segment(&mut output, APP1, &payload)?;
+            inserted = true;
+        }
+
+        match marker {
+            EOI => {
+                output.write_all(&[0xff, EOI])?;
+                return Ok(());
+            },
+            // Markers without a segment: TEM and RSTn
+            0x01 | 0xd0..=0xd7 => {
+                output.write_all(&[0xff, marker])?;
+                continue;
+            },
+            _ => {},
+        }
+
+        let mut length = [0; 2];
+        original.read_exact(&mut length)?;
+        let length = usize::from(u16::from_be_bytes(length));
+        if length < 2 {
+            return Err(ExifError::InvalidJpeg(format!("Segment {:x} with length {}", marker, length)));
+        }
+        let mut body = vec![0; length - 2];
+        original.read_exact(&mut body)?;
+
+        if marker == APP1 && body.starts_with(EXIF_HEADER) {
+            continue;
+        }
+        write_segment(&mut output, marker, &body)?;
+
+        if marker == SOS {
+            // The compressed image data follows, up to the end of the file
+            io::copy(&mut original, &mut output)?;
+            return Ok(());
+        }
+    }
+}
+
+/// Copy of a TIFF image with its EXIF data replaced by `exif`.
+///
+/// See `write_tiff_in_place()`.
+pub fn write_tiff(original: &[u8], exif: &ExifData) -> Result<Vec<u8>, ExifError> {
+    let mut file = Cursor::new(original.to_vec());
+    write_tiff_in_place(&mut file, exif)?;
+    Ok(file.into_inner())
+}
+
+/// Replaces the EXIF data of a TIFF file, without copying or moving the image data.
+///
+/// IFD-0 and its sub-IFDs are appended to the end of the file, and the header is
+/// pointed at the new IFD-0. The image data is not moved, so the StripOffsets and
+/// TileOffsets still point at it. The new IFD-0 links to the IFD that followed the
+/// old one, which keeps the thumbnail and the other pages.
+///
+/// The old IFD-0, its Exif, GPS and Interoperability IFDs and the values they hold
+/// out of line are then zeroed, so that removed or replaced metadata does not stay
+/// in the file. The old MakerNote is kept if `exif` still has one, since offsets
+/// inside it that are relative to the file are not updated.
+///
+/// `exif` must have the byte order of the file.
+pub fn write_tiff_in_place<F: Read + Write + Seek>(file: &mut F, exif: &ExifData) -> Result<(), ExifError> {
+    file.seek(SeekFrom::Start(0))?;
+    let mut header = [0; 8];
+    file.read_exact(&mut header)?;
+    let le = match header {
+        [b'I', b'I', 42, 0, ..] => true,
+        [b'M', b'M', 0, 42, ..] => false,
+        _ => return Err(ExifError::TiffBadPreamble("Not a TIFF file".to_string())),
+    };
+    if le != exif.byte_order().is_le() {
+        return Err(ExifError::ByteOrderMismatch);
+    }
+    let [_, _, _, _, ifd0 @ ..] = header;
+
+    // Pointer to the IFD after the old IFD-0
+    file.seek(SeekFrom::Start(u64::from(read_u32(le, ifd0))))?;
+    let mut count = [0; 2];
+    file.read_exact(&mut count)?;
+    file.seek(SeekFrom::Current(i64::from(read_u16(le, count)) * 12))?;
+    let mut next_ifd = [0; 4];
+    file.read_exact(&mut next_ifd)?;
+    let keep_maker_note = exif.get(ExifTag::MakerNote).is_some();
+    let old_ifds = old_ifd_ranges(file, le, read_u32(le, ifd0), keep_maker_note)?;
+
+    // IFDs begin on a word boundary
+    let end = file.seek(SeekFrom::End(0))?;
+    let mut appended = vec![0; (end % 2) as usize];
+    exif.serialize_ifds(&mut appended, end as usize, read_u32(le, next_ifd))?;
+    let size = end + appended.len() as u64;
+    if size > u64::from(u32::MAX) {
+        return Err(ExifError::TiffTooLarge { size });
+    }
+    file.write_all(&appended)?;
+
+    let ifd0 = (end + end % 2) as u32;
+    file.seek(SeekFrom::Start(4))?;
+    file.write_all(&if le { ifd0.to_le_bytes() } else { ifd0.to_be_bytes() })?;
+
+    // Only once nothing points at them any more
+    for (start, length) in old_ifds {
+        let length = length.min(end.saturating_sub(start));
+        file.seek(SeekFrom::Start(start))?;
+        io::copy(&mut io::repeat(0).take(length), file)?;
+    }
+    file.flush()?;
+    Ok(())
+}
+
+/// Positions and lengths of the IFD at `ifd0`, of its Exif, GPS and Interoperability
+/// IFDs, and of the values they hold out of line. Sub-IFDs that cannot be read are
+/// skipped.
+fn old_ifd_ranges<F: Read + Seek>(file: &mut F, le: bool, ifd0: u32, keep_maker_note: bool) -> io::Result<Vec<(u64, u64)>> {
+    let mut ranges = vec![];
+    let mut ifds = vec![ifd0];
+    while let Some(ifd) = ifds.pop() {
+        let start = u64::from(ifd);
+        if ranges.iter().any(|&(s, _)| s == start) {
+            continue;
+        }
+        let mut read_ifd = || -> io::Result<Vec<u8>> {
+            file.seek(SeekFrom::Start(start))?;
+            let mut count = [0; 2];
+            file.read_exact(&mut count)?;
+            let mut entries = vec![0; usize::from(read_u16(le, count)) * 12];
+            file.read_exact(&mut entries)?;
+            Ok(entries)
+        };
+        let entries = match read_ifd() {
+            Ok(entries) => entries,
+            Err(_) if ifd != ifd0 => continue,
+            Err(e) => return Err(e),
+        };
+        ranges.push((start, 2 + entries.len() as u64 + 4));
+
+        for entry in entries.chunks_exact(12) {
+            let (tag, format, count, value) = match *entry {
+                [t0, t1, f0, f1, c0, c1, c2, c3, v0, v1, v2, v3] => (
+                    read_u16(le, [t0, t1]),
+                    IfdFormat::new(read_u16(le, [f0, f1])),
+                    read_u32(le, [c0, c1, c2, c3]),
+                    read_u32(le, [v0, v1, v2, v3]),
+                ),
+                _ => continue,
+            };
+            let length = u64::from(format.size()) * u64::from(count);
+            match tag {
+                0x8769 | 0x8825 | 0xa005 => ifds.push(value),
+                0x927c if keep_maker_note => {},
+                _ if length > 4 => ranges.push((u64::from(value), length)),
+                _ => {},
+            }
+        }
+    }
+    Ok(ranges)
+}
+
+fn read_u16(le: bool, bytes: [u8; 2]) -> u16 {
+    if le { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) }
+}
+
+fn read_u32(le: bool, bytes: [u8; 4]) -> u32 {
+    if le { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) }
+}
+
+/// Copy of an image with its EXIF data replaced by `exif`, parsed from that
+/// image. The changed values are patched in place when they fit, and the rest of
+/// the file is left byte for byte as it was; otherwise, the EXIF data is written
+/// by `write_jpeg()`, `write_tiff()`, `write_png()` or `write_webp()`, according
+/// to `exif.mime`. PNG and WebP images are never patched.
+///
+/// See `ExifData::patches()`.
+pub fn write_patched(original: &[u8], exif: &ExifData) -> Result<Vec<u8>, ExifError> {
+    let patches = match exif.mime {
+        "image/png" | "image/webp" => None,
+        _ => exif.patches(original)?,
+    };
+    let patches = match patches {
+        Some(patches) => patches,
+        None => return match exif.mime {
+            "image/tiff" => write_tiff(original, exif),
+            "image/png" => write_png(original, exif),
+            "image/webp" => write_webp(original, exif),
+            _ => write_jpeg(original, exif),
+        },
+    };
+    let mut output = original.to_vec();
+    for patch in patches {
+        match output.get_mut(patch.offset..patch.offset + patch.data.len()) {
+            Some(bytes) => bytes.copy_from_slice(&patch.data),
+            None => return Err(ExifError::TiffTruncated),
+        }
+    }
+    Ok(output)
+}
+
+/// Copy of a PNG image with its EXIF data replaced by `exif`.
+///
+/// See `write_png_to()`.
+pub fn write_png(original: &[u8], exif: &ExifData) -> Result<Vec<u8>, ExifError> {
+    let mut output = Vec::with_capacity(original.len() + 4096);
+    write_png_to(original, exif, &mut output)?;
+    Ok(output)
+}
+
+/// Copies a PNG image from `original` to `output`, with its EXIF data replaced
+/// by `exif`. The image is streamed one chunk at a time.
+///
+/// The `eXIf` chunk is written right before the first IDAT chunk. Existing `eXIf`
+/// chunks are dropped; all other chunks are copied untouched.
+pub fn write_png_to<R: Read, W: Write>(mut original: R, exif: &ExifData, mut output: W) -> Result<(), ExifError> {
+    let payload = exif.serialize_tiff()?;
+
+    let mut signature = [0; 8];
+    original.read_exact(&mut signature)?;
+    if signature != PNG_SIGNATURE {
+        return Err(ExifError::InvalidPng("Missing PNG signature".to_string()));
+    }
+    output.write_all(&signature)?;
+
+    let mut inserted = false;
+    loop {
+        let mut header = [0; 8];
+        original.read_exact(&mut header)?;
+        let [l0, l1, l2, l3, t0, t1, t2, t3] = header;
+        let length = u32::from_be_bytes([l0, l1, l2, l3]);
+        let kind = [t0, t1, t2, t3];
+
+        if !inserted && (&kind == b"IDAT" || &kind == b"IEND") {
+            write_png_chunk(&mut output, b"eXIf", &payload)?;
+            inserted = true;
+        }
+
+        // The chunk data, followed by its CRC
+        let mut chunk = (&mut original).take(u64::from(length) + 4);
+        let copied = if &kind == b"eXIf" {
+            io::copy(&mut chunk, &mut io::sink())?
+        } else {
+            output.write_all(&header)?;
+            io::copy(&mut chunk, &mut output)?
+        };
+        if copied != u64::from(length) + 4 {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+
+        if &kind == b"IEND" {
+            return Ok(());
+        }
+    }
+}
+
+/// Copy of a WebP image with its EXIF data replaced by `exif`.
+///
+/// The `EXIF` chunk is written after the image data, and existing `EXIF` chunks
+/// are dropped. A simple WebP image gets a VP8X header, with the canvas size and
+/// alpha flag of its bitstream. The RIFF size is updated; all other chunks are
+/// copied untouched.
+pub fn write_webp(original: &[u8], exif: &ExifData) -> Result<Vec<u8>, ExifError> {
+    let payload = exif.serialize_tiff()?;
+
+    let (size, contents) = match *original {
+        [b'R', b'I', b'F', b'F', s0, s1, s2, s3, b'W', b'E', b'B', b'P', ref contents @ ..] => {
+            (u32::from_le_bytes([s0, s1, s2, s3]) as usize, contents)
+        },
+        _ => return Err(ExifError::InvalidWebp("Missing RIFF header".to_string())),
+    };
+    let mut rest = size.checked_sub(4)
+        .and_then(|size| contents.get(..size))
+        .ok_or_else(|| ExifError::InvalidWebp("RIFF data truncated".to_string()))?;
+
+    let mut chunks = vec![];
+    while !rest.is_empty() {
+        let (fourcc, data, tail) = match *rest {
+            [f0, f1, f2, f3, s0, s1, s2, s3, ref tail @ ..] => {
+                let length = u32::from_le_bytes([s0, s1, s2, s3]) as usize;
+                let data = tail.get(..length)
+                    .ok_or_else(|| ExifError::InvalidWebp("Chunk truncated".to_string()))?;
+                // Chunks are padded to an even size
+                ([f0, f1, f2, f3], data, tail.get(length + length % 2..).unwrap_or(&[]))
+            },
+            _ => return Err(ExifError::InvalidWebp("Chunk header truncated".to_string())),
+        };
+        if &fourcc != b"EXIF" {
+            chunks.push((fourcc, data));
+        }
+        rest = tail;
+    }
+
+    let vp8x = match chunks.first() {
+        Some(&(ref fourcc, data)) if fourcc == b"VP8X" => {
+            let mut vp8x = data.to_vec();
+            match vp8x.first_mut() {
+                Some(flags) => *flags |= WEBP_EXIF_FLAG,
+                None => return Err(ExifError::InvalidWebp("VP8X chunk truncated".to_string())),
+            }
+            chunks.remove(0);
+            vp8x
+        },
+        Some((fourcc, data)) => webp_vp8x(fourcc, data)?,
+        None => return Err(ExifError::InvalidWebp("No image data".to_string())),
+    };
+
+    // The EXIF chunk follows the image data, and comes before the XMP and unknown chunks
+    let position = chunks.iter()
+        .rposition(|(fourcc, _)| [b"ICCP", b"ANIM", b"ANMF", b"ALPH", b"VP8 ", b"VP8L"].contains(&fourcc))
+        .map_or(0, |i| i + 1);
+
+    let mut output = Vec::with_capacity(original.len() + payload.len() + 32);
+    output.extend(b"RIFF\0\0\0\0WEBP");
+    write_riff_chunk(&mut output, b"VP8X", &vp8x);
+    for (i, (fourcc, data)) in chunks.iter().enumerate() {
+        if i == position {
+            write_riff_chunk(&mut output, b"EXIF", &payload);
+        }
+        write_riff_chunk(&mut output, fourcc, data);
+    }
+    if position == chunks.len() {
+        write_riff_chunk(&mut output, b"EXIF", &payload);
+    }
+
+    let size = u32::try_from(output.len() - 8)
+        .map_err(|_| ExifError::InvalidWebp("RIFF data larger than 4 GiB".to_string()))?;
+    output.splice(4..8, size.to_le_bytes().iter().copied());
+    Ok(output)
+}
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+fn write_png_chunk<W: Write>(output: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    output.write_all(&(data.len() as u32).to_be_bytes())?;
+    output.write_all(kind)?;
+    output.write_all(data)?;
+    output.write_all(&crc32(&[kind, data]).to_be_bytes())
+}
+
+/// CRC-32 of the concatenated `parts`, as used by PNG
+fn crc32(parts: &[&[u8]]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in parts.iter().flat_map(|part| part.iter()) {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+const WEBP_EXIF_FLAG: u8 = 0x08;
+const WEBP_ALPHA_FLAG: u8 = 0x10;
+
+/// VP8X chunk for a simple WebP image, whose only chunk is the bitstream `data`
+fn webp_vp8x(fourcc: &[u8; 4], data: &[u8]) -> Result<Vec<u8>, ExifError> {
+    let (width, height, alpha) = match (fourcc, data) {
+        // Frame tag, start code, then 14-bit width and height
+        (b"VP8 ", &[_, _, _, 0x9d, 0x01, 0x2a, w0, w1, h0, h1, ..]) => {
+            (u32::from(u16::from_le_bytes([w0, w1]) & 0x3fff), u32::from(u16::from_le_bytes([h0, h1]) & 0x3fff), false)
+        },
+        // Signature, then 14-bit width - 1, 14-bit height - 1 and the alpha bit
+        (b"VP8L", &[0x2f, b0, b1, b2, b3, ..]) => {
+            let bits = u32::from_le_bytes([b0, b1, b2, b3]);
+            ((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1, bits & (1 << 28) != 0)
+        },
+        _ => return Err(ExifError::InvalidWebp(format!("Unexpected {} chunk", String::from_utf8_lossy(fourcc)))),
+    };
+    if width == 0 || height == 0 {
+        return Err(ExifError::InvalidWebp("Empty canvas".to_string()));
+    }
+
+    let flags = WEBP_EXIF_FLAG | if alpha { WEBP_ALPHA_FLAG } else { 0 };
+    let mut vp8x = vec![flags, 0, 0, 0];
+    vp8x.extend(&(width - 1).to_le_bytes()[..3]);
+    vp8x.extend(&(height - 1).to_le_bytes()[..3]);
+    Ok(vp8x)
+}
+
+fn write_riff_chunk(output: &mut Vec<u8>, fourcc: &[u8; 4], data: &[u8]) {
+    output.extend(fourcc);
+    output.extend(&(data.len() as u32).to_le_bytes());
+    output.extend(data);
+    if data.len() % 2 == 1 {
+        output.push(0);
+    }
+}
+
+/// Reads a marker, skipping the 0xff that may pad it
+fn read_marker<R: Read>(input: &mut R) -> Result<u8, ExifError> {
+    let mut byte = [0; 1];
+    input.read_exact(&mut byte)?;
+    if byte != [0xff] {
+        return Err(ExifError::InvalidJpeg(format!("Expected a marker, found {:x}", u8::from_be_bytes(byte))));
+    }
+    while byte == [0xff] {
+        input.read_exact(&mut byte)?;
+    }
+    Ok(u8::from_be_bytes(byte))
+}
+
+fn write_segment<W: Write>(output: &mut W, marker: u8, payload: &[u8]) -> io::Result<()> {
+    output.write_all(&[0xff, marker])?;
+    output.write_all(&(payload.len() as u16 + 2).to_be_bytes())?;
+    output.write_all(payload)
+}
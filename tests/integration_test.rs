@@ -11,11 +11,40 @@ const TIFF_PATTERN: &str = "*.tiff";
 const APP_MARKER: &[u8] = &[0xff, 0xd8, 0xff, 0xe1];
 
 #[cfg(test)]
-fn check_tags(entries: &Vec<ExifEntry>, expected_tags: Vec<ExifTag>) {
+fn check_tags(entries: &[ExifEntry], expected_tags: Vec<ExifTag>) {
     let tags: Vec<ExifTag> = entries.iter().map(|entry| entry.tag).collect();
     assert_eq!(tags, expected_tags);
 }
 
+/// Checks that IFD-0, its sub-IFDs and their values out of line start at even offsets
+fn assert_word_aligned(tiff: &[u8]) {
+    let le = tiff[0] == b'I';
+    let u16_at = |i: usize| {
+        let b = [tiff[i], tiff[i + 1]];
+        if le { u16::from_le_bytes(b) } else { u16::from_be_bytes(b) }
+    };
+    let u32_at = |i: usize| {
+        let b = [tiff[i], tiff[i + 1], tiff[i + 2], tiff[i + 3]];
+        if le { u32::from_le_bytes(b) } else { u32::from_be_bytes(b) }
+    };
+    // Bytes per value of each format, by format number
+    let widths = [0, 1, 1, 2, 4, 8, 1, 1, 2, 4, 8, 4, 8];
+
+    let mut ifds = vec![u32_at(4) as usize];
+    while let Some(ifd) = ifds.pop() {
+        assert_eq!(ifd % 2, 0, "IFD at {}", ifd);
+        for entry in (0..u16_at(ifd) as usize).map(|i| ifd + 2 + 12 * i) {
+            let (tag, format, count) = (u16_at(entry), u16_at(entry + 2) as usize, u32_at(entry + 4) as usize);
+            let offset = u32_at(entry + 8) as usize;
+            if matches!(tag, 0x8769 | 0x8825 | 0xa005) {
+                ifds.push(offset);
+            } else if widths.get(format).copied().unwrap_or(1) * count > 4 {
+                assert_eq!(offset % 2, 0, "value of tag {:#x} at {}", tag, offset);
+            }
+        }
+    }
+}
+
 #[test]
 fn test_parse_simple_morotola_jpeg() {
     let exif = rexif::parse_file("./tests/img/profile.jpg");
@@ -40,6 +69,714 @@ fn test_parse_simple_morotola_jpeg() {
             "Expected all tags to be from the standard namespace")
 }
 
+#[test]
+fn test_orientation() {
+    let exif = rexif::parse_file("./tests/img/profile.jpg").unwrap();
+    assert_eq!(exif.orientation(), Some(Orientation::Normal));
+
+    let o = Orientation::from_u16(6).unwrap();
+    assert_eq!(o, Orientation::Rotate90);
+    assert_eq!((o.rotation(), o.is_mirrored()), (90, false));
+    assert_eq!(o.output_dimensions(640, 480), (480, 640));
+    assert_eq!(o.transform_point(0, 0, 640, 480), Some((479, 0)));
+    assert_eq!(Orientation::Transpose.transform_point(10, 20, 640, 480), Some((20, 10)));
+    assert_eq!(Orientation::Transverse.transform_point(0, 0, 640, 480), Some((479, 639)));
+    assert_eq!(Orientation::Normal.transform_point(640, 0, 640, 480), None);
+    assert_eq!(o.transform_point(0, 480, 640, 480), None);
+    assert_eq!(Orientation::Rotate180.transform_point(0, 0, 0, 0), None);
+
+    assert_eq!(Orientation::MirrorHorizontal.then(Orientation::Rotate90), Orientation::Transverse);
+    assert_eq!(Orientation::Rotate90.then(Orientation::MirrorHorizontal), Orientation::Transpose);
+    assert_eq!(Orientation::Rotate90.then(Orientation::Rotate90.inverse()), Orientation::Normal);
+
+    // Rotating the pixels as the tag says leaves nothing to do; rotating less leaves the rest
+    assert_eq!(Orientation::Rotate270.after_rotation(270), Some(Orientation::Normal));
+    assert_eq!(Orientation::Rotate270.after_rotation(90), Some(Orientation::Rotate180));
+    assert_eq!(Orientation::Transverse.after_transform(Orientation::Transverse), Orientation::Normal);
+    assert_eq!(Orientation::Normal.after_rotation(45), None);
+}
+
+#[test]
+fn test_flash() {
+    let exif = rexif::parse_file("./tests/img/jpg/Konica_Minolta_DiMAGE_Z3.jpg").unwrap();
+    let flash = exif.flash().expect("Flash");
+    assert_eq!(flash, Flash {
+        fired: true,
+        return_light: FlashReturn::Detected,
+        mode: FlashMode::On,
+        function_present: true,
+        red_eye_reduction: false,
+    });
+    assert_eq!(flash.to_u16(), 0x0f);
+
+    let no_flash = rexif::parse_file("./tests/img/jpg/long_description.jpg").unwrap().flash().unwrap();
+    assert!(!no_flash.function_present, "{:?}", no_flash);
+    for n in 0..0x80 {
+        assert_eq!(Flash::from_u16(n).to_u16(), n);
+    }
+
+    // Little-endian TIFF with an Exif IFD holding Flash 0x1f: fired, return light
+    // detected, auto mode. The auto mode used to replace the return light.
+    let mut tiff = vec![b'I', b'I', 42, 0, 8, 0, 0, 0, 1, 0];
+    tiff.extend(&[0x69, 0x87, 4, 0, 1, 0, 0, 0, 26, 0, 0, 0]);
+    tiff.extend(&[0, 0, 0, 0, 1, 0]);
+    tiff.extend(&[0x09, 0x92, 3, 0, 1, 0, 0, 0, 0x1f, 0, 0, 0]);
+    tiff.extend(&[0, 0, 0, 0]);
+    let exif = rexif::parse_buffer_quiet(&tiff).0.unwrap();
+    let flash = exif.entries.iter().find(|e| e.tag == ExifTag::Flash).unwrap();
+    assert_eq!(flash.value_more_readable, "Fired. Strobe ret detected. Auto mode. No redeye reduction. ");
+
+    // A LONG Flash value that does not fit in 16 bits is not truncated
+    tiff[30..32].copy_from_slice(&[4, 0]);
+    tiff[36..40].copy_from_slice(&[1, 0, 1, 0]);
+    let exif = rexif::parse_buffer_quiet(&tiff).0.unwrap();
+    assert_eq!(exif.get_u32(ExifTag::Flash), Some(0x10001));
+    assert_eq!(exif.flash(), None);
+}
+
+#[test]
+fn test_typed_accessors() {
+    let mut exif = rexif::parse_file("./tests/img/jpg/Canon_40D.jpg").unwrap();
+    assert_eq!(exif.get_str(ExifTag::Model), Some("Canon EOS 40D"));
+    assert_eq!(exif.get_u32(ExifTag::ISOSpeedRatings), Some(100));
+    assert_eq!(exif.get_rational(ExifTag::FNumber), Some(URational { numerator: 71, denominator: 10 }));
+    assert_eq!(exif.get_in(IfdKind::Exif, ExifTag::FNumber).map(|e| e.kind), Some(IfdKind::Exif));
+    assert_eq!(exif.get_in(IfdKind::Ifd0, ExifTag::FNumber), None);
+    assert_eq!(exif.get_in(IfdKind::Gps, ExifTag::Model), None);
+
+    // Integers stored with the wrong format are coerced, and direct edits of the entries are seen
+    exif.entries.retain(|e| e.tag != ExifTag::Make);
+    let iso = exif.entries.iter_mut().find(|e| e.tag == ExifTag::ISOSpeedRatings).unwrap();
+    iso.value = TagValue::U32(vec![200]);
+    assert_eq!(exif.get_u32(ExifTag::ISOSpeedRatings), Some(200));
+    assert_eq!(exif.get(ExifTag::Make), None);
+    assert_eq!(exif.get_str(ExifTag::Model), Some("Canon EOS 40D"));
+}
+
+#[test]
+fn test_lens_info() {
+    // Little-endian TIFF with LensSpecification (last value unknown) and LensModel
+    let model = b"EF-S17-55mm f/2.8 IS USM\0";
+    let mut tiff = vec![b'I', b'I', 42, 0, 8, 0, 0, 0, 2, 0];
+    tiff.extend(&[0x32, 0xa4, 5, 0, 4, 0, 0, 0, 38, 0, 0, 0]);
+    tiff.extend(&[0x34, 0xa4, 2, 0, model.len() as u8, 0, 0, 0, 70, 0, 0, 0]);
+    tiff.extend(&[0, 0, 0, 0]);
+    for &(n, d) in &[(17u32, 1u32), (55, 1), (28, 10), (0, 0)] {
+        tiff.extend(&n.to_le_bytes());
+        tiff.extend(&d.to_le_bytes());
+    }
+    tiff.extend(&model[..]);
+
+    let lens = rexif::parse_buffer_quiet(&tiff).0.unwrap().lens().unwrap();
+    assert_eq!(lens, LensInfo {
+        make: None,
+        model: Some("EF-S17-55mm f/2.8 IS USM".to_string()),
+        serial_number: None,
+        min_focal_length: Some(17.0),
+        max_focal_length: Some(55.0),
+        min_f_number: Some(2.8),
+        max_f_number: Some(2.8),
+    });
+
+    // Without a lens specification, the focal length of the shot is used
+    let lens = rexif::parse_file("./tests/img/jpg/Canon_40D.jpg").unwrap().lens().unwrap();
+    assert_eq!((lens.min_focal_length, lens.max_focal_length), (Some(135.0), Some(135.0)));
+}
+
+#[test]
+fn test_exposure() {
+    let mut exif = rexif::parse_file("./tests/img/jpg/Canon_40D.jpg").unwrap();
+    let exposure = exif.exposure().unwrap();
+    assert_eq!(exposure.exposure_time, Some(1.0 / 160.0));
+    assert_eq!(exposure.f_number, Some(7.1));
+    assert_eq!(exposure.iso, Some(100));
+    assert_eq!(exposure.exposure_bias, Some(0.0));
+    let ev100 = exposure.ev100.unwrap();
+    assert!((ev100 - (7.1f64 * 7.1 * 160.0).log2()).abs() < 1e-9, "{}", ev100);
+    assert_eq!(exposure.light_value, Some(ev100));
+
+    // Falls back to the APEX values
+    exif.entries.retain(|e| e.tag != ExifTag::ExposureTime && e.tag != ExifTag::FNumber);
+    let exposure = exif.exposure().unwrap();
+    assert!((exposure.exposure_time.unwrap() * 160.0 - 1.0).abs() < 0.05, "{:?}", exposure);
+    assert!((exposure.f_number.unwrap() - 7.1).abs() < 0.1, "{:?}", exposure);
+}
+
+#[test]
+fn test_parse_warnings() {
+    // Little-endian TIFF with Orientation as LONG, an unknown tag, and Artist past the end
+    let mut tiff = vec![b'I', b'I', 42, 0, 8, 0, 0, 0, 3, 0];
+    tiff.extend(&[0x12, 0x01, 4, 0, 1, 0, 0, 0, 1, 0, 0, 0]);
+    tiff.extend(&[0x99, 0x99, 3, 0, 1, 0, 0, 0, 7, 0, 0, 0]);
+    tiff.extend(&[0x3b, 0x01, 2, 0, 100, 0, 0, 0, 0xe8, 3, 0, 0]);
+    tiff.extend(&[0, 0, 0, 0]);
+
+    let (exif, warnings) = rexif::parse_buffer_quiet(&tiff);
+    assert_eq!(exif.unwrap().entries.len(), 2);
+    assert_eq!(warnings, vec![
+        ParseWarning::WrongFormat {
+            tag: 0x0112,
+            exif_tag: ExifTag::Orientation,
+            kind: IfdKind::Ifd0,
+            expected: IfdFormat::U16,
+            found: IfdFormat::U32,
+            index: 0,
+            offset: 10,
+        },
+        ParseWarning::UnknownTag { tag: 0x9999, kind: IfdKind::Ifd0, format: IfdFormat::U16, index: 1, offset: 22 },
+        ParseWarning::DataBeyondEof {
+            tag: 0x013b,
+            kind: IfdKind::Ifd0,
+            data_offset: 1000,
+            length: 100,
+            index: 2,
+            offset: 34,
+        },
+    ]);
+    assert_eq!(warnings[0].to_string(), "EXIF tag 112 274 (Orientation), expected format 3 (U16), found 4 (U32)");
+
+    // In a JPEG, offsets are counted from the start of the file
+    let mut jpeg = vec![0xff, 0xd8, 0xff, 0xe1, 0, (2 + 6 + tiff.len()) as u8];
+    jpeg.extend(b"Exif\0\0");
+    jpeg.extend(&tiff);
+    jpeg.extend(&[0xff, 0xd9]);
+    let (exif, warnings) = rexif::parse_buffer_quiet(&jpeg);
+    let exif = exif.unwrap();
+    assert_eq!(exif.file_offset(0), 12);
+    assert_eq!(warnings.iter().map(|w| (w.offset(), w.entry_index())).collect::<Vec<_>>(),
+               vec![(22, Some(0)), (34, Some(1)), (46, Some(2))]);
+    // IFD0 is 8 bytes into the TIFF data
+    assert_eq!(exif.file_offset(8), 20);
+
+    // Errors say where the problem is
+    tiff[4] = 200;
+    let err = rexif::parse_buffer_quiet(&tiff).0.unwrap_err();
+    assert_eq!(err.offset(), Some(200));
+    assert_eq!(err.to_string(), "TIFF Exif IFD truncated: Truncated Ifd0 at dir entry count (50 < 202) (Ifd0 IFD at offset 200)");
+}
+
+#[test]
+fn test_parse_options() {
+    // IFD0 with Orientation, a GPS IFD pointer past the end, and one entry more than
+    // the data holds
+    let mut tiff = vec![b'I', b'I', 42, 0, 8, 0, 0, 0, 3, 0];
+    tiff.extend(&[0x12, 0x01, 3, 0, 1, 0, 0, 0, 6, 0, 0, 0]);
+    tiff.extend(&[0x25, 0x88, 4, 0, 1, 0, 0, 0, 0xe8, 3, 0, 0]);
+
+    let (exif, warnings) = rexif::parse_buffer_with_options(&tiff, &ParseOptions::lenient());
+    let exif = exif.unwrap();
+    assert_eq!(exif.entries.len(), 2);
+    assert_eq!(exif.orientation(), Some(Orientation::Rotate90));
+    assert_eq!(warnings, vec![
+        ParseWarning::IfdTruncated { kind: IfdKind::Ifd0, declared: 3, read: 2, offset: 8 },
+        ParseWarning::IfdSkipped { kind: IfdKind::Gps, reason: "IFD offset goes past EOF".to_string(), offset: 1000 },
+    ]);
+    assert_eq!(rexif::parse_buffer_quiet(&tiff).0.unwrap(), exif);
+
+    let (err, warnings) = rexif::parse_buffer_with_options(&tiff, &ParseOptions::strict());
+    match err {
+        Err(ExifError::NonConforming(ref w)) => assert_eq!(w, &warnings[0]),
+        other => panic!("Expected ExifError::NonConforming, found {:?}", other),
+    }
+    assert_eq!(err.unwrap_err().offset(), Some(8));
+
+    // Unknown tags are no reason to fail
+    let mut tiff = vec![b'I', b'I', 42, 0, 8, 0, 0, 0, 1, 0];
+    tiff.extend(&[0x99, 0x99, 3, 0, 1, 0, 0, 0, 7, 0, 0, 0]);
+    tiff.extend(&[0, 0, 0, 0]);
+    assert!(rexif::parse_buffer_with_options(&tiff, &ParseOptions::strict()).0.is_ok());
+}
+
+#[test]
+fn test_parse_limits() {
+    let jpeg = std::fs::read("./tests/img/jpg/Canon_40D.jpg").unwrap();
+    let parse = |options: ParseOptions| rexif::parse_buffer_with_options(&jpeg, &options).0;
+    assert!(parse(ParseOptions::default()).is_ok());
+
+    match parse(ParseOptions { max_entries: 10, ..ParseOptions::default() }) {
+        Err(ExifError::TooManyEntries { limit: 10 }) => {},
+        other => panic!("Expected ExifError::TooManyEntries, found {:?}", other),
+    }
+    match parse(ParseOptions { max_value_bytes: 16, ..ParseOptions::default() }) {
+        Err(e @ ExifError::ValueTooLarge { .. }) => assert!(e.offset().unwrap() > 12),
+        other => panic!("Expected ExifError::ValueTooLarge, found {:?}", other),
+    }
+    match parse(ParseOptions { max_total_bytes: 100, ..ParseOptions::default() }) {
+        Err(ExifError::TooMuchData { limit: 100 }) => {},
+        other => panic!("Expected ExifError::TooMuchData, found {:?}", other),
+    }
+    match parse(ParseOptions { max_ifd_depth: 0, ..ParseOptions::default() }) {
+        Err(ExifError::IfdTooDeep { kind: IfdKind::Exif, limit: 0, .. }) => {},
+        other => panic!("Expected ExifError::IfdTooDeep, found {:?}", other),
+    }
+    match parse(ParseOptions { max_ifds: 1, ..ParseOptions::default() }) {
+        Err(ExifError::TooManyIfds { limit: 1 }) => {},
+        other => panic!("Expected ExifError::TooManyIfds, found {:?}", other),
+    }
+
+    // A count of 4 Gi bytes is only a warning if the data is not in the file
+    let mut tiff = vec![b'I', b'I', 42, 0, 8, 0, 0, 0, 1, 0];
+    tiff.extend(&[0x3b, 0x01, 2, 0, 0xff, 0xff, 0xff, 0xff, 8, 0, 0, 0]);
+    tiff.extend(&[0, 0, 0, 0]);
+    let (exif, warnings) = rexif::parse_buffer_quiet(&tiff);
+    assert!(exif.is_ok());
+    assert!(matches!(warnings[0], ParseWarning::DataBeyondEof { .. }));
+}
+
+#[test]
+fn test_malformed_input_does_not_panic() {
+    // Exif segment without any TIFF data
+    let jpeg = [0xff, 0xd8, 0xff, 0xe1, 0, 8, b'E', b'x', b'i', b'f', 0, 0, 0xff, 0xd9];
+    match rexif::parse_buffer_quiet(&jpeg).0 {
+        Err(ExifError::TiffTruncated) => {},
+        other => panic!("Expected ExifError::TiffTruncated, found {:?}", other),
+    }
+
+    // Every truncation of a real file
+    let jpeg = std::fs::read("./tests/img/jpg/Canon_40D.jpg").unwrap();
+    for len in 0..2048 {
+        let _ = rexif::parse_buffer_quiet(&jpeg[..len]);
+        let _ = rexif::parse_buffer_with_options(&jpeg[..len], &ParseOptions::strict());
+    }
+}
+
+#[test]
+fn test_exif_builder() {
+    for &byte_order in &[ByteOrder::LittleEndian, ByteOrder::BigEndian] {
+        let exif = ExifBuilder::new(byte_order)
+            .set(ExifTag::Artist, "Somebody")
+            .set(ExifTag::ImageDescription, "A description longer than four bytes")
+            .set(ExifTag::Orientation, 6u16)
+            .set(ExifTag::ExposureTime, URational { numerator: 1, denominator: 250 })
+            .set(ExifTag::InteroperabilityIndex, "R98")
+            .set(ExifTag::RawToPreviewGain, TagValue::F64(vec![1.5]))
+            .set_gps(-33.856784, 151.215297)
+            .build()
+            .unwrap();
+        assert_eq!(exif.byte_order(), byte_order);
+        let kinds: Vec<_> = exif.entries.iter().map(|e| (e.kind, e.tag)).collect();
+        assert_eq!(kinds, vec![
+            (IfdKind::Ifd0, ExifTag::ImageDescription),
+            (IfdKind::Ifd0, ExifTag::Orientation),
+            (IfdKind::Ifd0, ExifTag::Artist),
+            (IfdKind::Ifd0, ExifTag::ExifOffset),
+            (IfdKind::Ifd0, ExifTag::GPSOffset),
+            (IfdKind::Ifd0, ExifTag::RawToPreviewGain),
+            (IfdKind::Exif, ExifTag::ExposureTime),
+            (IfdKind::Exif, ExifTag::InteropOffset),
+            (IfdKind::Gps, ExifTag::GPSVersionID),
+            (IfdKind::Gps, ExifTag::GPSLatitudeRef),
+            (IfdKind::Gps, ExifTag::GPSLatitude),
+            (IfdKind::Gps, ExifTag::GPSLongitudeRef),
+            (IfdKind::Gps, ExifTag::GPSLongitude),
+            (IfdKind::Interoperability, ExifTag::InteroperabilityIndex),
+        ]);
+
+        let serialized = exif.serialize().unwrap();
+        assert_word_aligned(&exif.serialize_tiff().unwrap());
+        // Floats follow the byte order of the file, like the integers
+        let gain = if byte_order.is_le() { 1.5f64.to_le_bytes() } else { 1.5f64.to_be_bytes() };
+        assert!(serialized.windows(8).any(|w| w == gain));
+        let jpeg = [APP_MARKER, &(serialized.len() as u16 + 2).to_be_bytes(), &serialized].concat();
+        let (parsed, warnings) = rexif::parse_buffer_quiet(&jpeg);
+        let parsed = parsed.unwrap();
+        assert_eq!(warnings, vec![]);
+        assert_eq!(parsed.entries.len(), exif.entries.len());
+        assert_eq!(parsed.get_str(ExifTag::ImageDescription), Some("A description longer than four bytes"));
+        assert_eq!(parsed.orientation(), Some(Orientation::Rotate90));
+        assert_eq!(parsed.get_str(ExifTag::InteroperabilityIndex), Some("R98"));
+        assert_eq!(parsed.get(ExifTag::RawToPreviewGain).unwrap().value, TagValue::F64(vec![1.5]));
+        let gps = parsed.gps().unwrap();
+        assert!((gps.latitude.unwrap() + 33.856784).abs() < 1e-6);
+        assert!((gps.longitude.unwrap() - 151.215297).abs() < 1e-6);
+    }
+
+    let build = |tag, value: TagValue| ExifBuilder::new(ByteOrder::LittleEndian).set(tag, value).build();
+    match build(ExifTag::Artist, 1u16.into()) {
+        Err(ExifError::WrongTagFormat { expected: IfdFormat::Ascii, found: IfdFormat::U16, .. }) => {},
+        other => panic!("Expected ExifError::WrongTagFormat, found {:?}", other),
+    }
+    match build(ExifTag::Orientation, vec![1u16, 2].into()) {
+        Err(ExifError::WrongTagCount { min: 1, max: 1, found: 2, .. }) => {},
+        other => panic!("Expected ExifError::WrongTagCount, found {:?}", other),
+    }
+    match build(ExifTag::UnknownToMe, 1u16.into()) {
+        Err(ExifError::TagNotInIfd { .. }) => {},
+        other => panic!("Expected ExifError::TagNotInIfd, found {:?}", other),
+    }
+    match ExifBuilder::new(ByteOrder::LittleEndian).set_gps(91.0, 0.0).build() {
+        Err(ExifError::InvalidTagValue { tag: ExifTag::GPSLatitude, .. }) => {},
+        other => panic!("Expected ExifError::InvalidTagValue, found {:?}", other),
+    }
+}
+
+#[test]
+fn test_edit_parsed_exif() {
+    let mut exif = rexif::parse_file("./tests/img/jpg/gps/DSCN0029.jpg").unwrap();
+    assert!(exif.get(ExifTag::GPSOffset).is_some());
+
+    exif.set(ExifTag::Artist, "Somebody".into()).unwrap();
+    exif.set(ExifTag::Orientation, 8u16.into()).unwrap();
+    exif.set(ExifTag::ResolutionUnit, 3u16.into()).unwrap();
+    let x_resolution = exif.get(ExifTag::XResolution).unwrap();
+    assert!(x_resolution.value_more_readable.ends_with("pixels per cm"), "{}", x_resolution.value_more_readable);
+    assert!(exif.set(ExifTag::Orientation, "up".into()).is_err());
+    assert!(exif.set(ExifTag::ExifOffset, 0u32.into()).is_err());
+
+    let removed = exif.remove(ExifTag::Make);
+    assert_eq!(removed.len(), 1);
+    assert!(exif.get(ExifTag::Make).is_none());
+
+    // Removing every GPS tag drops the GPS IFD
+    exif.retain(|e| e.kind != IfdKind::Gps);
+    assert!(exif.get(ExifTag::GPSOffset).is_none());
+
+    let serialized = exif.serialize().unwrap();
+    let jpeg = [APP_MARKER, &(serialized.len() as u16 + 2).to_be_bytes(), &serialized].concat();
+    let parsed = rexif::parse_buffer_quiet(&jpeg).0.unwrap();
+    assert_eq!(parsed.get_str(ExifTag::Artist), Some("Somebody"));
+    assert_eq!(parsed.orientation(), Some(Orientation::Rotate270));
+    assert!(parsed.get(ExifTag::Make).is_none());
+    assert!(parsed.gps().is_none());
+    assert_eq!(parsed.entries.len(), exif.entries.len());
+
+    // Setting a GPS tag brings the GPS IFD back
+    exif.set(ExifTag::GPSAltitude, URational { numerator: 10, denominator: 1 }.into()).unwrap();
+    assert_eq!(exif.get(ExifTag::GPSOffset).map(|e| e.kind), Some(IfdKind::Ifd0));
+    assert_eq!(exif.get(ExifTag::GPSVersionID).map(|e| &e.value), Some(&TagValue::U8(vec![2, 3, 0, 0])));
+    let serialized = exif.serialize().unwrap();
+    let jpeg = [APP_MARKER, &(serialized.len() as u16 + 2).to_be_bytes(), &serialized].concat();
+    let (parsed, warnings) = rexif::parse_buffer_quiet(&jpeg);
+    let parsed = parsed.unwrap();
+    assert!(!warnings.iter().any(|w| w.is_non_conforming()), "{:?}", warnings);
+    let gps_tags: Vec<_> = parsed.entries.iter().filter(|e| e.kind == IfdKind::Gps).map(|e| e.tag).collect();
+    assert_eq!(gps_tags, vec![ExifTag::GPSVersionID, ExifTag::GPSAltitude]);
+
+    // Text that is not ASCII takes the UTF-8 type, which replacing it keeps
+    exif.set(ExifTag::Artist, "Zoë".into()).unwrap();
+    assert_eq!(exif.get(ExifTag::Artist).unwrap().ifd.format, IfdFormat::Utf8);
+    exif.set(ExifTag::Artist, "Zoe".into()).unwrap();
+    assert_eq!(exif.get(ExifTag::Artist).unwrap().ifd.format, IfdFormat::Utf8);
+    exif.set(ExifTag::Copyright, "Zoe".into()).unwrap();
+    assert_eq!(exif.get(ExifTag::Copyright).unwrap().ifd.format, IfdFormat::Ascii);
+    let artist = ExifEntry::new(IfdKind::Ifd0, ExifTag::Artist, "Zoë".into(), true).unwrap();
+    assert_eq!(artist.ifd.format, IfdFormat::Utf8);
+    exif.set(ExifTag::Artist, "Zoë".into()).unwrap();
+    let serialized = exif.serialize().unwrap();
+    let jpeg = [APP_MARKER, &(serialized.len() as u16 + 2).to_be_bytes(), &serialized].concat();
+    let (parsed, warnings) = rexif::parse_buffer_quiet(&jpeg);
+    assert!(!warnings.iter().any(|w| w.is_non_conforming()), "{:?}", warnings);
+    assert_eq!(parsed.unwrap().get_str(ExifTag::Artist), Some("Zoë"));
+}
+
+#[test]
+fn test_write_jpeg() {
+    // The image data from the last start of scan on. The first one may be in the thumbnail.
+    fn scan(jpeg: &[u8]) -> &[u8] {
+        let sos = jpeg.windows(2).rposition(|w| w == [0xff, 0xda]).unwrap();
+        &jpeg[sos..]
+    }
+
+    let original = std::fs::read("./tests/img/jpg/Canon_40D.jpg").unwrap();
+    let mut exif = rexif::parse_buffer_quiet(&original).0.unwrap();
+    exif.set(ExifTag::Artist, "Somebody".into()).unwrap();
+    let written = rexif::write_jpeg(&original, &exif).unwrap();
+    assert_eq!(scan(&written), scan(&original));
+    let parsed = rexif::parse_buffer_quiet(&written).0.unwrap();
+    assert_eq!(parsed.get_str(ExifTag::Artist), Some("Somebody"));
+    assert_eq!(parsed.entries.len(), exif.entries.len());
+
+    let mut streamed = vec![];
+    rexif::write_jpeg_to(std::io::Cursor::new(&original), &exif, &mut streamed).unwrap();
+    assert_eq!(streamed, written);
+
+    // Without EXIF data, the segment goes after the JFIF segment. Canon_40D.jpg has
+    // the JFIF segment at 2, and the Exif segment at 20.
+    let app1_end = 22 + u16::from_be_bytes([original[22], original[23]]) as usize;
+    let original = [&original[..20], &original[app1_end..]].concat();
+    assert!(rexif::parse_buffer_quiet(&original).0.is_err());
+    let written = rexif::write_jpeg(&original, &exif).unwrap();
+    assert_eq!(&written[..4], &[0xff, 0xd8, 0xff, 0xe0]);
+    assert_eq!(&written[20..22], &[0xff, 0xe1]);
+    assert_eq!(scan(&written), scan(&original));
+    let parsed = rexif::parse_buffer_quiet(&written).0.unwrap();
+    assert_eq!(parsed.get_str(ExifTag::Artist), Some("Somebody"));
+
+    exif.set(ExifTag::ImageDescription, "x".repeat(70000).into()).unwrap();
+    match rexif::write_jpeg(&original, &exif) {
+        Err(ExifError::JpegSegmentTooLarge { .. }) => {},
+        other => panic!("Expected ExifError::JpegSegmentTooLarge, found {:?}", other.map(|v| v.len())),
+    }
+    assert!(rexif::write_jpeg(b"GIF89a", &exif).is_err());
+}
+
+#[test]
+fn test_write_tiff() {
+    for path in glob(&format!("{}/{}", TIFF_TEST_DIR, TIFF_PATTERN)).unwrap() {
+        let path = path.unwrap();
+        let original = std::fs::read(&path).unwrap();
+        let mut exif = rexif::parse_buffer_quiet(&original).0.unwrap();
+        exif.set(ExifTag::Artist, "Somebody".into()).unwrap();
+        let written = rexif::write_tiff(&original, &exif).unwrap();
+
+        // The new IFDs go at the end, and the old IFD-0 is zeroed; the image data stays
+        assert_eq!(&written[..4], &original[..4], "{:?}", path);
+        let old_ifd0 = [original[4], original[5], original[6], original[7]];
+        let old_ifd0 = if exif.byte_order().is_le() { u32::from_le_bytes(old_ifd0) } else { u32::from_be_bytes(old_ifd0) } as usize;
+        assert_eq!(&written[old_ifd0..old_ifd0 + 2], &[0, 0], "{:?}", path);
+        let numbers = |tag| match exif.get(tag).map(|e| &e.value) {
+            Some(TagValue::U32(v)) => v.iter().map(|&n| n as usize).collect(),
+            Some(TagValue::U16(v)) => v.iter().map(|&n| n as usize).collect(),
+            _ => vec![],
+        };
+        for (&offset, &length) in numbers(ExifTag::StripOffsets).iter().zip(&numbers(ExifTag::StripByteCounts)) {
+            assert_eq!(&written[offset..offset + length], &original[offset..offset + length], "{:?}", path);
+        }
+        assert_word_aligned(&written);
+        let parsed = rexif::parse_buffer_quiet(&written).0.unwrap();
+        assert_eq!(parsed.get_str(ExifTag::Artist), Some("Somebody"), "{:?}", path);
+        assert_eq!(parsed.entries.len(), exif.entries.len(), "{:?}", path);
+        assert_eq!(
+            parsed.get(ExifTag::StripOffsets).map(|e| &e.value),
+            exif.get(ExifTag::StripOffsets).map(|e| &e.value),
+            "{:?}", path);
+
+        let mut file = std::io::Cursor::new(original.clone());
+        rexif::write_tiff_in_place(&mut file, &exif).unwrap();
+        assert_eq!(file.into_inner(), written);
+
+        let mut swapped = original.clone();
+        swapped[..4].copy_from_slice(if exif.byte_order().is_le() { b"MM\0*" } else { b"II*\0" });
+        match rexif::write_tiff(&swapped, &exif) {
+            Err(ExifError::ByteOrderMismatch) => {},
+            other => panic!("Expected ExifError::ByteOrderMismatch, found {:?}", other.map(|v| v.len())),
+        }
+    }
+    assert!(rexif::write_tiff(b"GIF89a", &rexif::parse_file("./tests/img/jpg/Canon_40D.jpg").unwrap()).is_err());
+}
+
+#[test]
+fn test_write_png_and_webp() {
+    fn crc32(bytes: &[u8]) -> u32 {
+        let mut crc = !0u32;
+        for &byte in bytes {
+            crc ^= u32::from(byte);
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+            }
+        }
+        !crc
+    }
+    fn png_chunks(png: &[u8]) -> Vec<(&[u8], &[u8])> {
+        let mut chunks = vec![];
+        let mut rest = &png[8..];
+        while !rest.is_empty() {
+            let length = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+            let crc = &rest[8 + length..12 + length];
+            assert_eq!(crc, &crc32(&rest[4..8 + length]).to_be_bytes()[..]);
+            chunks.push((&rest[4..8], &rest[8..8 + length]));
+            rest = &rest[12 + length..];
+        }
+        chunks
+    }
+    fn riff_chunks(webp: &[u8]) -> Vec<(&[u8], &[u8])> {
+        assert_eq!(u32::from_le_bytes([webp[4], webp[5], webp[6], webp[7]]) as usize, webp.len() - 8);
+        let mut chunks = vec![];
+        let mut rest = &webp[12..];
+        while !rest.is_empty() {
+            let length = u32::from_le_bytes([rest[4], rest[5], rest[6], rest[7]]) as usize;
+            chunks.push((&rest[..4], &rest[8..8 + length]));
+            rest = &rest[8 + length + length % 2..];
+        }
+        chunks
+    }
+
+    let mut exif = rexif::parse_file("./tests/img/jpg/Canon_40D.jpg").unwrap();
+    exif.set(ExifTag::Artist, "Somebody".into()).unwrap();
+    let tiff = exif.serialize_tiff().unwrap();
+    assert_eq!(&exif.serialize().unwrap()[6..], &tiff[..]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    for &(kind, data) in &[(&b"IHDR"[..], &[0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0][..]), (b"IDAT", b"pixels"), (b"IEND", b"")] {
+        png.extend(&(data.len() as u32).to_be_bytes());
+        png.extend(kind);
+        png.extend(data);
+        png.extend(&crc32(&[kind, data].concat()).to_be_bytes());
+    }
+    let written = rexif::write_png(&png, &exif).unwrap();
+    let kinds: Vec<_> = png_chunks(&written).iter().map(|c| c.0).collect();
+    assert_eq!(kinds, [&b"IHDR"[..], b"eXIf", b"IDAT", b"IEND"]);
+    assert_eq!(png_chunks(&written)[1].1, &tiff[..]);
+    assert_eq!(rexif::write_png(&written, &exif).unwrap(), written);
+    exif.mime = "image/png";
+    assert_eq!(rexif::write_patched(&png, &exif).unwrap(), written);
+    assert!(rexif::write_png(&png[..png.len() - 2], &exif).is_err());
+
+    // A simple lossless WebP of 3x2 pixels with alpha
+    let mut webp = b"RIFF\x16\0\0\0WEBPVP8L\x09\0\0\0\x2f".to_vec();
+    webp.extend(&(2u32 | 1 << 14 | 1 << 28).to_le_bytes());
+    webp.extend(b"data\0");
+    let written = rexif::write_webp(&webp, &exif).unwrap();
+    let chunks = riff_chunks(&written);
+    let kinds: Vec<_> = chunks.iter().map(|c| c.0).collect();
+    assert_eq!(kinds, [&b"VP8X"[..], b"VP8L", b"EXIF"]);
+    assert_eq!(chunks[0].1, &[0x18, 0, 0, 0, 2, 0, 0, 1, 0, 0][..]);
+    assert_eq!(chunks[1].1, &webp[20..29]);
+    assert_eq!(chunks[2].1, &tiff[..]);
+    assert_eq!(rexif::write_webp(&written, &exif).unwrap(), written);
+    exif.mime = "image/webp";
+    assert_eq!(rexif::write_patched(&webp, &exif).unwrap(), written);
+    assert!(rexif::write_webp(&webp[..webp.len() - 2], &exif).is_err());
+    match rexif::write_webp(&png, &exif) {
+        Err(ExifError::InvalidWebp(_)) => {},
+        other => panic!("Expected ExifError::InvalidWebp, found {:?}", other.map(|v| v.len())),
+    }
+}
+
+#[test]
+fn test_redact() {
+    let parse = || rexif::parse_file("./tests/img/jpg/gps/DSCN0029.jpg").unwrap();
+    let original = parse();
+    let kinds = |exif: &ExifData, kind| exif.entries.iter().filter(|e| e.kind == kind).count();
+    assert!(kinds(&original, IfdKind::Gps) > 0);
+
+    let mut exif = parse();
+    let removed = exif.redact(&RedactOptions::new(Redaction::Gps));
+    assert_eq!(removed.len(), kinds(&original, IfdKind::Gps));
+    assert_eq!(kinds(&exif, IfdKind::Gps), 0);
+    assert!(exif.get(ExifTag::GPSOffset).is_none());
+    assert!(exif.gps().is_none());
+    assert_eq!(exif.get(ExifTag::Make), original.get(ExifTag::Make));
+    let parsed = rexif::parse_buffer(&exif.serialize().unwrap()[6..]).unwrap();
+    assert_eq!(kinds(&parsed, IfdKind::Gps), 0);
+
+    let mut exif = parse();
+    exif.set(ExifTag::BodySerialNumber, "1234".into()).unwrap();
+    exif.set(ExifTag::CameraOwnerName, "Somebody".into()).unwrap();
+    let removed = exif.redact(&RedactOptions::new(Redaction::Identifiers));
+    assert!(removed.iter().any(|e| e.tag == ExifTag::BodySerialNumber));
+    assert!(removed.iter().any(|e| e.tag == ExifTag::CameraOwnerName));
+    assert!(exif.get(ExifTag::MakerNote).is_none());
+    assert_eq!(kinds(&exif, IfdKind::Gps), kinds(&original, IfdKind::Gps));
+
+    let mut exif = parse();
+    exif.redact(&RedactOptions::new(Redaction::All).keep_orientation_tag(true));
+    assert_eq!(exif.entries.len(), 1);
+    assert_eq!(exif.orientation(), original.orientation());
+    exif.redact(&RedactOptions::new(Redaction::All));
+    assert!(exif.entries.is_empty());
+    assert!(rexif::write_jpeg(&std::fs::read("./tests/img/jpg/gps/DSCN0029.jpg").unwrap(), &exif).is_ok());
+
+    let mut exif = parse();
+    exif.redact(&RedactOptions::new(Redaction::Tags(vec![ExifTag::Model, ExifTag::ExifOffset])));
+    assert!(exif.get(ExifTag::Model).is_none());
+    assert_eq!(kinds(&exif, IfdKind::Exif), 0);
+    assert_eq!(exif.get(ExifTag::Make), original.get(ExifTag::Make));
+
+    // Writing a TIFF leaves nothing of the removed entries in the file
+    let tiff = std::fs::read("./tests/img/tiff/Crémieux11.tiff").unwrap();
+    let mut exif = rexif::parse_buffer_quiet(&tiff).0.unwrap();
+    let artist = exif.get_str(ExifTag::Artist).unwrap().as_bytes().to_vec();
+    assert!(tiff.windows(artist.len()).any(|w| w == &artist[..]));
+    exif.redact(&RedactOptions::new(Redaction::Tags(vec![ExifTag::Artist])));
+    let written = rexif::write_tiff(&tiff, &exif).unwrap();
+    assert!(!written.windows(artist.len()).any(|w| w == &artist[..]));
+    assert!(rexif::parse_buffer_quiet(&written).0.unwrap().get(ExifTag::Artist).is_none());
+}
+
+#[test]
+fn test_write_patched() {
+    let original = std::fs::read("./tests/img/jpg/Canon_40D.jpg").unwrap();
+    let differing = |a: &[u8], b: &[u8]| a.iter().zip(b).filter(|(x, y)| x != y).count();
+
+    let mut exif = rexif::parse_buffer_quiet(&original).0.unwrap();
+    assert_eq!(exif.patches(&original).unwrap(), Some(vec![]));
+    exif.set(ExifTag::Orientation, 6u16.into()).unwrap();
+    exif.set(ExifTag::Model, "EOS 40".into()).unwrap();
+    let patches = exif.patches(&original).unwrap().unwrap();
+    assert_eq!(patches.len(), 3);
+    let written = rexif::write_patched(&original, &exif).unwrap();
+    assert_eq!(written.len(), original.len());
+    assert!(differing(&written, &original) <= patches.iter().map(|p| p.data.len()).sum());
+    let parsed = rexif::parse_buffer_quiet(&written).0.unwrap();
+    assert_eq!(parsed.orientation(), Some(Orientation::Rotate90));
+    assert_eq!(parsed.get_str(ExifTag::Model), Some("EOS 40"));
+    assert_eq!(parsed.entries.len(), exif.entries.len());
+
+    // A longer value, or a new tag, does not fit
+    exif.set(ExifTag::Model, "A much longer model name".into()).unwrap();
+    assert_eq!(exif.patches(&original).unwrap(), None);
+    let written = rexif::write_patched(&original, &exif).unwrap();
+    let parsed = rexif::parse_buffer_quiet(&written).0.unwrap();
+    assert_eq!(parsed.get_str(ExifTag::Model), Some("A much longer model name"));
+
+    let mut exif = rexif::parse_buffer_quiet(&original).0.unwrap();
+    exif.set(ExifTag::Artist, "Somebody".into()).unwrap();
+    assert_eq!(exif.patches(&original).unwrap(), None);
+    // The same number of entries, but one that was not in the file
+    exif.remove(ExifTag::Model);
+    assert_eq!(exif.patches(&original).unwrap(), None);
+    // Entries are found by tag, wherever they are in `entries`
+    let mut exif = rexif::parse_buffer_quiet(&original).0.unwrap();
+    exif.entries.reverse();
+    assert_eq!(exif.patches(&original).unwrap(), Some(vec![]));
+}
+
+#[test]
+fn test_set_byte_order() {
+    let parse = || rexif::parse_file("./tests/img/jpg/Canon_40D.jpg").unwrap();
+    let original = parse();
+    let other = if original.le { ByteOrder::BigEndian } else { ByteOrder::LittleEndian };
+
+    let mut exif = parse();
+    exif.set_byte_order(other).unwrap();
+    assert_eq!(exif.byte_order(), other);
+    let serialized = exif.serialize().unwrap();
+    assert_eq!(&serialized[6..8], if original.le { b"MM" } else { b"II" });
+    let parsed = rexif::parse_buffer(&serialized[6..]).unwrap();
+    assert_eq!(parsed.byte_order(), other);
+    assert_eq!(parsed.entries.len(), original.entries.len());
+    for (converted, entry) in parsed.entries.iter().zip(&original.entries) {
+        assert_eq!(converted.tag, entry.tag);
+        if ![ExifTag::ExifOffset, ExifTag::GPSOffset, ExifTag::InteropOffset].contains(&entry.tag) {
+            assert_eq!(converted.value_more_readable, entry.value_more_readable, "{:?}", entry.tag);
+        }
+    }
+
+    // Back to the original order, the serialization is the same
+    exif.set_byte_order(original.byte_order()).unwrap();
+    assert_eq!(exif.serialize().unwrap(), original.serialize().unwrap());
+
+    let mut exif = parse();
+    exif.entries[0].ifd.format = IfdFormat::Unknown;
+    match exif.set_byte_order(other) {
+        Err(ExifError::UnknownFormat { kind: IfdKind::Ifd0, .. }) => {},
+        other => panic!("Expected ExifError::UnknownFormat, found {:?}", other),
+    }
+    assert_eq!(exif.byte_order(), original.byte_order());
+    assert!(exif.set_byte_order(original.byte_order()).is_ok());
+
+    // Raw data that could not be parsed has no known layout either
+    let mut exif = parse();
+    let orientation = exif.entries.iter_mut().find(|e| e.tag == ExifTag::Orientation).unwrap();
+    orientation.value = TagValue::Invalid(orientation.ifd.data.clone(), original.le, 3, 1);
+    match exif.set_byte_order(other) {
+        Err(ExifError::UnknownFormat { kind: IfdKind::Ifd0, tag: 0x0112 }) => {},
+        other => panic!("Expected ExifError::UnknownFormat, found {:?}", other),
+    }
+
+    // Floats are converted like integers
+    let mut exif = ExifBuilder::new(ByteOrder::LittleEndian)
+        .set(ExifTag::RawToPreviewGain, TagValue::F64(vec![1.5]))
+        .build()
+        .unwrap();
+    exif.set_byte_order(ByteOrder::BigEndian).unwrap();
+    let serialized = exif.serialize_tiff().unwrap();
+    assert!(serialized.windows(8).any(|w| w == 1.5f64.to_be_bytes()));
+    let parsed = rexif::parse_buffer(&serialized).unwrap();
+    assert_eq!(parsed.get(ExifTag::RawToPreviewGain).unwrap().value, TagValue::F64(vec![1.5]));
+}
+
 #[test]
 fn test_parse_jpeg_without_metadata() {
     let exif = rexif::parse_file("./tests/img/invalid/no_exif.jpg");
@@ -76,12 +813,158 @@ fn test_parse_jpeg_with_gps() -> Result<(), std::io::Error> {
         assert!(exif.entries.iter().find(|&e| e.tag == t).is_some(), "Could not find exif tag: {:?}", t);
     }
 
+    let gps = exif.gps().expect("GPS info");
+    assert!((gps.latitude.unwrap() - (43.0 + 28.0 / 60.0 + 5.68 / 3600.0)).abs() < 1e-5, "{:?}", gps);
+    assert!((gps.longitude.unwrap() - (11.0 + 52.0 / 60.0 + 48.62 / 3600.0)).abs() < 1e-5, "{:?}", gps);
+    assert_eq!(gps.altitude, None);
+    let timestamp = gps.timestamp.unwrap().duration_since(std::time::UNIX_EPOCH).unwrap();
+    assert_eq!(timestamp.as_millis(), 1_224_773_120_910);
+    assert_eq!(gps.map_datum.as_deref(), Some("WGS-84"));
+
+    // Years out of range give no timestamp
+    let mut exif = exif;
+    for year in &["300000000000", "9223372036854775807", "65535"] {
+        let date = exif.entries.iter_mut().find(|e| e.tag == ExifTag::GPSDateStamp).unwrap();
+        date.value = TagValue::Ascii(format!("{}:10:23", year));
+        assert_eq!(exif.gps().expect("GPS info").timestamp.is_some(), *year == "65535", "{}", year);
+    }
+
     Ok(())
 }
 
+#[test]
+fn test_exif_date_time() {
+    let exif = rexif::parse_file("./tests/img/jpg/Olympus_C8080WZ.jpg").unwrap();
+    let date = exif.date_time().expect("DateTime");
+    assert_eq!(date.to_string(), "2008-07-31T13:03:47.5");
+    assert_eq!(date.to_system_time(), None);
+    assert_eq!(exif.date_time_original().unwrap().to_string(), "2006-10-22T15:44:29");
+
+    let date = ExifDateTime::parse("2008-10-23 14:45:20", Some("91 "), Some("+02:00")).unwrap();
+    assert_eq!(date.to_string(), "2008-10-23T14:45:20.91+02:00");
+    let timestamp = date.to_system_time().unwrap().duration_since(std::time::UNIX_EPOCH).unwrap();
+    assert_eq!(timestamp.as_millis(), 1_224_765_920_910);
+
+    assert_eq!(ExifDateTime::parse("2008/10/23   :  :  ", None, Some("   :  ")).unwrap().to_string(), "2008-10-23T00:00:00");
+    assert_eq!(ExifDateTime::parse("0000:00:00 00:00:00", None, None), None);
+    assert_eq!(ExifDateTime::parse("    :  :     :  :  ", None, None), None);
+
+    // Days past the end of the month, counting leap years
+    assert_eq!(ExifDateTime::parse("2020:02:29 12:00:00", None, None).unwrap().to_string(), "2020-02-29T12:00:00");
+    assert_eq!(ExifDateTime::parse("2021:02:29 12:00:00", None, None), None);
+    assert_eq!(ExifDateTime::parse("2020:02:31 12:00:00", None, None), None);
+    assert_eq!(ExifDateTime::parse("2021:04:31 12:00:00", None, None), None);
+    assert!(ExifDateTime::parse("2000:02:29", None, None).is_some());
+    assert_eq!(ExifDateTime::parse("1900:02:29", None, None), None);
+    let date = ExifDateTime { day: 31, offset: Some(0), ..ExifDateTime::parse("2021:04:30", None, None).unwrap() };
+    assert_eq!(date.to_system_time(), None);
+}
+
+#[test]
+fn test_parse_tiff_baseline_tags() {
+    let exif = rexif::parse_file("./tests/img/tiff/Crémieux11.tiff");
+    assert!(exif.is_ok(), "{:?}", exif);
+
+    let exif = exif.unwrap();
+    assert_eq!(exif.mime, "image/tiff");
+
+    let readable = |tag| exif.entries.iter().find(|e| e.tag == tag).map(|e| e.value_more_readable.to_string());
+    assert_eq!(readable(ExifTag::ImageWidth).as_deref(), Some("199"));
+    assert_eq!(readable(ExifTag::ImageLength).as_deref(), Some("47"));
+    assert_eq!(readable(ExifTag::BitsPerSample).as_deref(), Some("8, 8, 8, 8"));
+    assert_eq!(readable(ExifTag::Compression).as_deref(), Some("LZW"));
+    assert_eq!(readable(ExifTag::PhotometricInterpretation).as_deref(), Some("RGB"));
+    assert_eq!(readable(ExifTag::ExtraSamples).as_deref(), Some("Associated alpha"));
+    assert_eq!(readable(ExifTag::Artist).as_deref(), Some("Jean Cornillon"));
+}
+
+#[test]
+fn test_parse_dng_tags() {
+    // Little-endian TIFF with DNGVersion and a 3x3 ColorMatrix1 stored after IFD0
+    let mut tiff = vec![b'I', b'I', 42, 0, 8, 0, 0, 0, 2, 0];
+    tiff.extend(&[0x12, 0xc6, 1, 0, 4, 0, 0, 0, 1, 4, 0, 0]);
+    tiff.extend(&[0x21, 0xc6, 10, 0, 9, 0, 0, 0, 38, 0, 0, 0]);
+    tiff.extend(&[0, 0, 0, 0]);
+    for n in &[10000i32, -5000, 0, 0, 10000, 0, -2500, 0, 10000] {
+        tiff.extend(&n.to_le_bytes());
+        tiff.extend(&10000i32.to_le_bytes());
+    }
+
+    let (exif, warnings) = rexif::parse_buffer_quiet(&tiff);
+    let exif = exif.unwrap();
+    assert!(warnings.is_empty(), "{:?}", warnings);
+    assert_eq!(exif.entries[0].tag, ExifTag::DNGVersion);
+    assert_eq!(exif.entries[0].value_more_readable, "1.4.0.0");
+    assert_eq!(exif.entries[1].tag, ExifTag::ColorMatrix1);
+    assert_eq!(
+        exif.entries[1].value_more_readable,
+        "[1.0000, -0.5000, 0.0000] [0.0000, 1.0000, 0.0000] [-0.2500, 0.0000, 1.0000]"
+    );
+}
+
+#[test]
+fn test_parse_interop_ifd() {
+    let exif = rexif::parse_file("./tests/img/jpg/Canon_40D.jpg").unwrap();
+
+    let index = exif.entries.iter().find(|e| e.tag == ExifTag::InteroperabilityIndex);
+    let index = index.expect("InteroperabilityIndex not found");
+    assert_eq!(index.kind, IfdKind::Interoperability);
+    assert_eq!(index.value, TagValue::Ascii("R98".to_string()));
+
+    // Interoperability tags 0x0001 and 0x0002 must not be mistaken for GPS tags
+    assert!(exif.entries.iter().all(|e| e.tag != ExifTag::GPSLatitudeRef && e.tag != ExifTag::GPSLatitude));
+}
+
+#[test]
+fn test_parse_utf8_format() {
+    // Big-endian TIFF with an Artist tag of the Exif 3.0 UTF-8 type (129)
+    let artist = "Zoë Müller\0".as_bytes();
+    let mut tiff = vec![b'M', b'M', 0, 42, 0, 0, 0, 8, 0, 1];
+    tiff.extend(&[0x01, 0x3b, 0, 129, 0, 0, 0, artist.len() as u8, 0, 0, 0, 26]);
+    tiff.extend(&[0, 0, 0, 0]);
+    tiff.extend(artist);
+
+    let (exif, warnings) = rexif::parse_buffer_quiet(&tiff);
+    let exif = exif.unwrap();
+    assert!(warnings.is_empty(), "{:?}", warnings);
+    assert_eq!(exif.entries[0].tag, ExifTag::Artist);
+    assert_eq!(exif.entries[0].ifd.format, IfdFormat::Utf8);
+    assert_eq!(exif.entries[0].value, TagValue::Ascii("Zoë Müller".to_string()));
+
+    let serialized = exif.serialize().unwrap();
+    assert_eq!(serialized, tiff);
+}
+
+#[test]
+fn test_encoded_text() {
+    let text = |code: &[u8], data: &[u8], le: bool| {
+        TagValue::Undefined([code, data].concat(), le).to_encoded_text().unwrap()
+    };
+    const JIS: &[u8] = b"JIS\0\0\0\0\0";
+    const UNICODE: &[u8] = b"UNICODE\0";
+
+    // ISO-2022-JP, Shift-JIS and EUC-JP codes of "日本"
+    let iso_2022_jp = text(JIS, &[0x1b, 0x24, 0x42, 0x46, 0x7c, 0x4b, 0x5c, 0x1b, 0x28, 0x42], false);
+    assert_eq!(iso_2022_jp, EncodedText { encoding: TextEncoding::Jis, text: "日本".to_string() });
+    assert_eq!(text(JIS, &[0x93, 0xfa, 0x96, 0x7b, 0], false).text, "日本");
+    assert_eq!(text(JIS, &[0xc6, 0xfc, 0xcb, 0xdc], false).text, "日本");
+    // Printable ASCII stays ASCII, even when it could be read as bare JIS X 0208 codes
+    assert_eq!(text(JIS, b"OK", false).text, "OK");
+    assert_eq!(text(JIS, &[0x46, 0x7c, 0x4b, 0x5c], false).text, "F|K\\");
+
+    // UCS-2 with a byte order mark, or in the byte order opposite to the TIFF container
+    assert_eq!(text(UNICODE, &[0xfe, 0xff, 0, b'O', 0, b'K'], true).text, "OK");
+    assert_eq!(text(UNICODE, &[b'O', 0, b'K', 0, 0, 0], false).text, "OK");
+    assert_eq!(text(UNICODE, &[0, b'O', 0, b'K'], false).text, "OK");
+
+    assert_eq!(text(b"ASCII\0\0\0", b"Hi\0\0", true).encoding, TextEncoding::Ascii);
+    assert_eq!(TagValue::Undefined(b"FOO\0\0\0\0\0".to_vec(), true).to_encoded_text(), None);
+}
+
 #[cfg(test)]
 fn cmp_serialized_exif_with_original<P: AsRef<Path>>(file: P) -> Result<(), std::io::Error> {
     let parsed_exif1 = parse_file(&file).unwrap();
+    assert_word_aligned(&parsed_exif1.serialize_tiff().unwrap());
 
     let serialized_exif1 = parsed_exif1.serialize().unwrap();
     let serialized_exif1 = if parsed_exif1.mime == "image/jpeg" {